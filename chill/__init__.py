@@ -1,3 +1,27 @@
 from .core import Chill
+from .chill import (
+    Profile,
+    Thermostat,
+    PidController,
+    Fluid,
+    Convection,
+    ChillError,
+    ChillValidationError,
+    ChillEdgeTypeError,
+    ChillInstabilityError,
+    ChillSolverError,
+)
 
-__all__ = ['Chill']
+__all__ = [
+    'Chill',
+    'Profile',
+    'Thermostat',
+    'PidController',
+    'Fluid',
+    'Convection',
+    'ChillError',
+    'ChillValidationError',
+    'ChillEdgeTypeError',
+    'ChillInstabilityError',
+    'ChillSolverError',
+]
@@ -4,11 +4,11 @@ from matplotlib.figure import Figure
 import matplotlib.cm as colormap
 import matplotlib.colors as mcolors
 from dataclasses import dataclass
-from typing import List, Tuple, Dict, Optional
+from typing import Callable, List, Tuple, Dict, Optional, Union
 from thermo import Chemical
-from tqdm import tqdm
 import networkx as nx
-from .chill import process
+from tqdm import tqdm
+from .chill import Network, Profile, Thermostat, PidController, Convection
 from .constants import *
 
 @dataclass
@@ -20,10 +20,19 @@ class Node:
         temperature (float): Initial temperature of the node.
         capacity (float): Thermal capacity of the node
         name (str): Name of the node.
+        schedule (float, tuple, Profile or callable, optional): Prescribed temperature of a
+            boundary node. None for a node that is integrated.
+        heat_capacity (tuple, Profile or callable, optional): Temperature-dependent capacity
+            C(T) of the node, None for a constant capacity.
+        phase_change (tuple, optional): (melt_temperature, latent_heat, width) of a node that
+            melts and freezes, None for no phase change.
     """
     temperature: float = 300.0   # [K]
     capacity: float = 100.0      # [K/J]
     name: str = ''
+    schedule: Optional[Union[float, Tuple[np.ndarray, np.ndarray], Profile, Callable[[float], float]]] = None
+    heat_capacity: Optional[Union[Tuple[np.ndarray, np.ndarray], Profile, Callable[[float], float]]] = None
+    phase_change: Optional[Tuple[float, float, float]] = None
 
 @dataclass
 class Edge:
@@ -35,11 +44,22 @@ class Edge:
         parameter (float): Parameter associated with the edge (e.g., thermal conductivity).
         edge_type (int): Type of the edge (e.g., conduction, radiation, heat input).
         name (str): Name of the edge.
+        schedule (tuple, Profile or callable, optional): Power profile of a heat input edge,
+            None for a constant parameter.
+        conductivity (tuple, optional): (conductivity, geometry, mode) of a conduction edge
+            whose conductance depends on temperature, None for a constant resistance.
+        convection (Convection, optional): Power law or correlation of a convection edge.
+        mass_flow (tuple, optional): (mass_flow, specific_heat) of an advection edge whose
+            mass flow varies in time, None for a constant capacity rate.
     """
     nodes: Tuple[Node, Node]
     parameter: float
     edge_type: int
     name: str = ''
+    schedule: Optional[Union[Tuple[np.ndarray, np.ndarray], Profile, Callable[[float], float]]] = None
+    conductivity: Optional[Tuple[object, float, str]] = None
+    convection: Optional[Convection] = None
+    mass_flow: Optional[Tuple[object, float]] = None
 
 class Chill:
     """
@@ -50,42 +70,143 @@ class Chill:
     TYPE_TRANSFER = 0
     TYPE_RADIATION = 1
     TYPE_HEAT_INPUT = 2
+    TYPE_CONVECTION = 3
+    TYPE_ADVECTION = 4
+
+    # Largest number of progress bar updates of `execute`, each a call to the core
+    PROGRESS_UPDATES = 100
 
-    def __init__(self, dt: float = 0.1):
+    def __init__(self, dt: float = 0.1, method: str = 'euler', dt_limit: str = 'none',
+                 on_failure: str = 'raise', max_delta: float = 1e8,
+                 temperature_range: Optional[Tuple[float, float]] = None, min_dt: Optional[float] = None):
         """
         Initializes the Chill simulation.
 
         Args:
             dt (float): Time step for the simulation [seconds]. Default is 0.1 seconds.
+            method (str): Time integration scheme, 'euler', 'rk4', 'implicit' or 'crank_nicolson'.
+                Default is 'euler'.
+            dt_limit (str): Handling of the stable time step of the explicit schemes,
+                'none', 'enforce' (raise) or 'substep'. Default is 'none'.
+            on_failure (str): What a failing step does, 'raise' or 'stop' (keep the last
+                good state and report it in `failure`). Default is 'raise'.
+            max_delta (float): Largest temperature change of a node in a single step [K]
+                before the step fails. Default is 1e8.
+            temperature_range (tuple, optional): Allowed (min, max) temperature [K]; a step
+                leaving it fails. Default is None (unbounded).
+            min_dt (float, optional): Redo a failing step as halves, recursively, down to this
+                step size before it counts as failed. Default is None (disabled).
         """
         self.nodes: List[Node] = []
         self.edges: List[Edge] = []
         self.dt: float = dt  # seconds
+        self.method: str = method
+        self.dt_limit: str = dt_limit
+        self.on_failure: str = on_failure
+        self.max_delta: float = max_delta
+        self.temperature_range: Optional[Tuple[float, float]] = temperature_range
+        self.min_dt: Optional[float] = min_dt
+        self.failure: Optional[Dict] = None
         self.time: float = 0.0
         self.ready: bool = False
         self.temperatures_history: List[np.ndarray] = []
         self.times_history: List[float] = []
+        self.edge_flows_history: List[np.ndarray] = []
+        # (controller class, heater edge, sensor node, further arguments)
+        self.controllers: List[Tuple[type, Edge, Node, tuple]] = []
         self._node_dict: Dict[str, Node] = {}
 
-    def define_node(self, temperature: float, capacity: float, name: str = '') -> Node:
+    def define_node(self, temperature: float, capacity, name: str = '') -> Node:
         """
         Defines a new node and adds it to the simulation.
 
         Args:
             temperature (float): Initial temperature of the node [K].
-            capacity (float): Thermal capacity of the node [K/J].
+            capacity (float, tuple, Profile or callable): Thermal capacity of the node [K/J], or
+                a temperature-dependent capacity given as (temperatures, capacities), as a
+                Profile over temperature or as a function of temperature. Such a node is
+                stepped in enthalpy, which conserves energy however steeply C varies. A
+                function is called some tens of times per step, a table is much cheaper.
             name (str, optional): Name of the node. Defaults to an empty string.
 
         Returns:
             Node: The created node object.
         """
-        node = Node(temperature=temperature, capacity=capacity, name=name)
+        heat_capacity = None
+        if isinstance(capacity, tuple) or callable(capacity):
+            heat_capacity = capacity
+            capacity = self._property_value(capacity, temperature)
+        node = Node(temperature=temperature, capacity=capacity, name=name, heat_capacity=heat_capacity)
         self.nodes.append(node)
         if name:
             self._node_dict[name] = node  # Add to dictionary if name is provided
         self.ready = False  # Invalidate setup as a new node is added
         return node
 
+    def define_phase_change_node(self, temperature: float, capacity, melt_temperature: float,
+                                 latent_heat: float, width: float, name: str = '') -> Node:
+        """
+        Defines a node that melts and freezes, such as a phase-change material buffer.
+        The latent heat is absorbed evenly over a mushy zone of `width` centred on the
+        melting temperature; the node is stepped in enthalpy.
+
+        Args:
+            temperature (float): Initial temperature of the node [K].
+            capacity (float, tuple, Profile or callable): Sensible heat capacity of the node
+                [J/K], constant or temperature-dependent as in `define_node`.
+            melt_temperature (float): Melting temperature [K].
+            latent_heat (float): Latent heat of the whole node [J].
+            width (float): Width of the mushy zone [K].
+            name (str, optional): Name of the node. Defaults to an empty string.
+
+        Returns:
+            Node: The created node object.
+        """
+        node = self.define_node(temperature, capacity, name=name)
+        node.phase_change = (melt_temperature, latent_heat, width)
+        return node
+
+    def define_boundary(self, temperature, name: str = '') -> Node:
+        """
+        Defines a boundary node whose temperature is prescribed rather than integrated.
+
+        Args:
+            temperature (float, tuple, Profile or callable): Constant temperature [K], or a
+                schedule given as (times, temperatures) that is linearly interpolated in the
+                simulated time, as a Profile, or as a function of the simulated time.
+            name (str, optional): Name of the node. Defaults to an empty string.
+
+        Returns:
+            Node: The created node object.
+        """
+        node = self.define_node(temperature=self._schedule_value(temperature), capacity=np.inf, name=name)
+        node.schedule = temperature
+        return node
+
+    def _schedule_value(self, schedule) -> float:
+        """
+        Value of a constant, a (times, values) table, a Profile or a function of time
+        at the current time.
+        """
+        if callable(schedule):
+            return float(schedule(self.time))
+        if isinstance(schedule, tuple):
+            times, values = schedule
+            return float(np.interp(self.time, times, values))
+        return float(schedule)
+
+    @staticmethod
+    def _property_value(curve, temperature: float) -> float:
+        """
+        Value of a constant, a (temperatures, values) table, a Profile or a function of
+        temperature at the given temperature.
+        """
+        if callable(curve):
+            return float(curve(temperature))
+        if isinstance(curve, tuple):
+            return float(np.interp(temperature, *curve))
+        return float(curve)
+
     def define_edge(self, node0: Node, node1: Node, parameter: float, edge_type: int, name: str = '') -> None:
         """
         Defines a new edge between two nodes and adds it to the simulation.
@@ -163,6 +284,66 @@ class Chill:
         """
         self.define_edge(node0, node1, conductance, self.TYPE_TRANSFER, name=name)
 
+    def define_conductive_link(self, node0: Node, node1: Node, conductivity, area: float, length: float,
+                               mode: str = 'mean', name: str = '') -> None:
+        """
+        Defines a thermal conduction edge whose conductance follows the temperature-dependent
+        conductivity of its material, re-evaluated at every step.
+
+        Args:
+            node0 (Node): One end of the conduction edge.
+            node1 (Node): The other end of the conduction edge.
+            conductivity (float, tuple, Profile or callable): Thermal conductivity [W/(m K)] as a
+                table (temperatures, conductivities), as a Profile over temperature or as a
+                function of temperature.
+            area (float): Cross-section of the link [m^2].
+            length (float): Length of the link [m].
+            mode (str, optional): 'mean' evaluates the conductivity at the mean temperature of
+                the two nodes, 'integral' uses the conductivity integral between them.
+                Defaults to 'mean'.
+            name (str, optional): Name of the edge. Defaults to an empty string.
+        """
+        geometry = area / length
+        k = self._property_value(conductivity, 0.5 * (node0.temperature + node1.temperature))
+        self.define_edge(node0, node1, 1 / (geometry * k), self.TYPE_TRANSFER, name=name)
+        self.edges[-1].conductivity = (conductivity, geometry, mode)
+
+    def define_convection(self, node0: Node, node1: Node, convection: Convection, name: str = '') -> None:
+        """
+        Defines a convection edge between two nodes, e.g. a surface and the surrounding fluid.
+
+        Args:
+            node0 (Node): One end of the convection edge.
+            node1 (Node): The other end of the convection edge.
+            convection (Convection): The heat transfer coefficient, a power law
+                h = C * |dT|^n or a correlation (vertical plate, horizontal cylinder, forced
+                flow over a plate) evaluated at the film temperature at every step.
+            name (str, optional): Name of the edge. Defaults to an empty string.
+        """
+        parameter = convection.parameter(node0.temperature, node1.temperature)
+        self.define_edge(node0, node1, parameter, self.TYPE_CONVECTION, name=name)
+        self.edges[-1].convection = convection
+
+    def define_advection(self, node0: Node, node1: Node, mass_flow, specific_heat: float,
+                         name: str = '') -> None:
+        """
+        Defines an advection edge: a fluid flowing from node0 to node1 carries
+        mass_flow * specific_heat * (T0 - T1) into node1. Chain the nodes of a duct or a
+        pumped loop with one advection edge per section.
+
+        Args:
+            node0 (Node): The upstream node.
+            node1 (Node): The downstream node.
+            mass_flow (float, tuple, Profile or callable): Mass flow [kg/s], or a profile
+                given as (times, flows), as a Profile or as a function of time.
+            specific_heat (float): Specific heat capacity of the fluid [J/(kg K)].
+            name (str, optional): Name of the edge. Defaults to an empty string.
+        """
+        rate = self._schedule_value(mass_flow) * specific_heat
+        self.define_edge(node0, node1, rate, self.TYPE_ADVECTION, name=name)
+        if isinstance(mass_flow, tuple) or callable(mass_flow):
+            self.edges[-1].mass_flow = (mass_flow, specific_heat)
+
     def define_thermal_radiation(self, node0: Node, node1: Node, constant: float, name: str = '') -> None:
         """
         Defines a thermal radiation edge between two nodes.
@@ -175,17 +356,20 @@ class Chill:
         """
         self.define_edge(node0, node1, constant, self.TYPE_RADIATION, name=name)
 
-    def define_thermal_input(self, node0: Node, node1: Node, heat_input: float, name: str = '') -> None:
+    def define_thermal_input(self, node0: Node, node1: Node, heat_input, name: str = '') -> None:
         """
         Defines a thermal input edge between two nodes.
 
         Args:
             node0 (Node): One end of the heat input edge.
             node1 (Node): The other end of the heat input edge.
-            heat_input (float): Amount of heat input.
+            heat_input (float, tuple, Profile or callable): Amount of heat input [W], or a power
+                profile given as (times, powers), as a Profile or as a function of time.
             name (str, optional): Name of the edge. Defaults to an empty string.
         """
-        self.define_edge(node0, node1, heat_input, self.TYPE_HEAT_INPUT, name=name)
+        self.define_edge(node0, node1, self._schedule_value(heat_input), self.TYPE_HEAT_INPUT, name=name)
+        if isinstance(heat_input, tuple) or callable(heat_input):
+            self.edges[-1].schedule = heat_input
 
     def define_thermal_conduction_by_name(self, node_name0: str, node_name1: str, conductance: float, name: str = '') -> None:
         """
@@ -237,15 +421,66 @@ class Chill:
     
         Args:
             target_node (Node): The node to which the heater is connected.
-            heat_input (float): The amount of heat input provided by the heater.
+            heat_input (float, tuple or Profile): The amount of heat input provided by the heater,
+                or its power profile.
     
         Returns:
             Node : The created node object (heater).
         """
-        node = self.define_node(temperature=300*K, capacity=np.inf)
+        node = self.define_boundary(300*K)
         self.define_thermal_input(node, target_node, heat_input)
         return node
 
+    def define_thermostat(self, sensor_node: Node, target_node: Node, power: float,
+                          on_temperature: float, off_temperature: float) -> Node:
+        """
+        Defines a heater on the target node switched by a thermostat with hysteresis on the
+        sensor node. The heater switches on when the sensor cools down to `on_temperature`
+        and off when it warms up to `off_temperature`, evaluated at every step.
+
+        Args:
+            sensor_node (Node): The node whose temperature is sensed.
+            target_node (Node): The node to which the heater is connected.
+            power (float): Heater power while it is on [W].
+            on_temperature (float): Switch-on temperature [K].
+            off_temperature (float): Switch-off temperature [K], above `on_temperature`.
+
+        Returns:
+            Node : The created node object (heater).
+        """
+        if not on_temperature < off_temperature:
+            raise ValueError("on_temperature must be below off_temperature")
+        node = self.define_heater(target_node, 0.)
+        self.controllers.append((Thermostat, self.edges[-1], sensor_node,
+                                 (power, on_temperature, off_temperature)))
+        return node
+
+    def define_pid_heater(self, sensor_node: Node, target_node: Node, setpoint: float, kp: float,
+                          ki: float = 0., kd: float = 0., min_power: float = 0.,
+                          max_power: float = np.inf, period: float = 0.) -> Node:
+        """
+        Defines a heater on the target node whose power is set by a PID controller holding
+        the sensor node at the set point. The controller runs inside the step loop.
+
+        Args:
+            sensor_node (Node): The node whose temperature is controlled.
+            target_node (Node): The node to which the heater is connected.
+            setpoint (float): Target temperature of the sensor node [K].
+            kp (float): Proportional gain [W/K].
+            ki (float, optional): Integral gain [W/(K s)]. Defaults to 0.
+            kd (float, optional): Derivative gain [W s/K]. Defaults to 0.
+            min_power (float, optional): Lower power limit [W]. Defaults to 0.
+            max_power (float, optional): Upper power limit [W]. Defaults to inf.
+            period (float, optional): Control period [s], at least one step. Defaults to 0.
+
+        Returns:
+            Node : The created node object (heater).
+        """
+        node = self.define_heater(target_node, 0.)
+        self.controllers.append((PidController, self.edges[-1], sensor_node,
+                                 (setpoint, kp, ki, kd, min_power, max_power, period)))
+        return node
+
     def setup(self) -> None:
         """
         Sets up the simulation by initializing necessary data structures based on defined nodes and edges.
@@ -264,13 +499,74 @@ class Chill:
         self.connections = np.array([
             [node_index_map[id(node)] for node in edge.nodes]
             for edge in self.edges
-        ], dtype=np.uint64)
+        ], dtype=np.uint64).reshape(-1, 2)
 
         # Convert edge types to a NumPy array
         self.edge_types = np.array([edge.edge_type for edge in self.edges], dtype=np.int32)
 
+        # Compile the network once; run() only steps it
+        self.network = Network(
+            self.temperatures,
+            self.capacities,
+            self.parameters,
+            self.connections,
+            self.edge_types,
+            self.dt,
+            method=self.method,
+            dt_limit=self.dt_limit,
+            max_delta=self.max_delta,
+            temperature_range=self.temperature_range,
+            on_failure=self.on_failure,
+            min_dt=self.min_dt
+        )
+        self.network.time = self.time
+        for index, node in enumerate(self.nodes):
+            if node.schedule is not None:
+                self.network.set_boundary(index, node.schedule)
+            if node.heat_capacity is not None:
+                self.network.set_heat_capacity(index, node.heat_capacity)
+            if node.phase_change is not None:
+                self.network.set_phase_change(index, *node.phase_change)
+        for index, edge in enumerate(self.edges):
+            if edge.schedule is not None:
+                self.network.set_heat_input(index, edge.schedule)
+            if edge.convection is not None:
+                self.network.set_convection(index, edge.convection)
+            if edge.mass_flow is not None:
+                self.network.set_mass_flow(index, *edge.mass_flow)
+            if edge.conductivity is not None:
+                conductivity, geometry, mode = edge.conductivity
+                self.network.set_conductivity(index, conductivity, geometry, mode=mode)
+        edge_index_map = {id(edge): idx for idx, edge in enumerate(self.edges)}
+        for controller, edge, sensor, args in self.controllers:
+            self.network.add_controller(
+                controller(edge_index_map[id(edge)], node_index_map[id(sensor)], *args)
+            )
+        # What the network last saw, to hand later edits of the arrays over to it
+        self._synced_parameters = self.parameters.copy()
+        self._synced_capacities = self.capacities.copy()
+
         self.ready = True  # Mark setup as complete
 
+    def set_edge_parameter(self, edge_index: int, parameter: float) -> None:
+        """
+        Changes the parameter of an edge without repeating the setup.
+
+        Args:
+            edge_index (int): Index of the edge in `edges`.
+            parameter (float): New parameter of the edge.
+        """
+        self.edges[edge_index].parameter = parameter
+        self.edges[edge_index].schedule = None
+        self.edges[edge_index].conductivity = None
+        self.edges[edge_index].convection = None
+        self.edges[edge_index].mass_flow = None
+        self.controllers = [c for c in self.controllers if c[1] is not self.edges[edge_index]]
+        if self.ready:
+            self.parameters[edge_index] = parameter
+            self._synced_parameters[edge_index] = parameter
+            self.network.set_parameter(edge_index, parameter)
+
     def run(self, steps: int = 100) -> None:
         """
         Runs the simulation for a specified number of steps.
@@ -278,6 +574,9 @@ class Chill:
         Args:
             steps (int, optional): Number of simulation steps to run. Defaults to 100.
 
+        If a step fails, the simulation keeps the last good state. With on_failure='stop'
+        the failure is reported in `failure`, otherwise the ChillError is raised.
+
         Raises:
             RuntimeError: If the setup has not been completed.
         """
@@ -285,16 +584,158 @@ class Chill:
             raise RuntimeError("Setup must be called before running the simulation.")
 
         # Execute the simulation process
-        self.temperatures = process(
-            self.temperatures,
-            self.capacities,
-            self.parameters,
-            self.connections,
-            self.edge_types,
-            self.dt,
-            steps
-        )
-        self.time += self.dt * steps  # Update simulation time
+        self._push_state()
+        try:
+            self.network.step(steps)
+        finally:
+            self._sync_network()
+
+    def _push_state(self) -> None:
+        """
+        Hands `dt`, `time` and the edits of `temperatures`, `parameters` and `capacities`
+        since the network last ran over to it. An edited parameter or capacity replaces
+        whatever made it vary.
+        """
+        self.network.dt = self.dt
+        self.network.time = self.time
+        if not np.array_equal(self.temperatures, self.network.temperatures):
+            self.network.temperatures = self.temperatures
+        for index in np.flatnonzero(self.parameters != self._synced_parameters):
+            self.set_edge_parameter(int(index), float(self.parameters[index]))
+        for index in np.flatnonzero(self.capacities != self._synced_capacities):
+            node = self.nodes[index]
+            node.capacity = float(self.capacities[index])
+            node.heat_capacity = None
+            node.phase_change = None
+            self.network.set_capacity(int(index), node.capacity)
+            self._synced_capacities[index] = node.capacity
+
+    def _sync_network(self) -> None:
+        """
+        Takes over the state reached by the network, also after a failed step.
+        """
+        self.temperatures = self.network.temperatures
+        self.parameters = self.network.parameters
+        self.capacities = self.network.capacities
+        self._synced_parameters = self.parameters.copy()
+        self._synced_capacities = self.capacities.copy()
+        self.time = self.network.time
+        self.failure = self.network.failure
+
+    def edge_flows(self) -> np.ndarray:
+        """
+        Heat flow through each edge at the current temperatures, from its first to its second node.
+
+        Returns:
+            np.ndarray: Heat flow of each edge in `edges` [W].
+
+        Raises:
+            RuntimeError: If the setup has not been completed.
+        """
+        if not self.ready:
+            raise RuntimeError("Setup must be called before computing the edge flows.")
+
+        return self.network.edge_flows
+
+    def controller_states(self) -> List[Dict]:
+        """
+        State of each controller, e.g. the duty cycle and switching history of a thermostat
+        or the output of a PID controller.
+
+        Returns:
+            list: One dict per controller, see `Network.controllers`.
+
+        Raises:
+            RuntimeError: If the setup has not been completed.
+        """
+        if not self.ready:
+            raise RuntimeError("Setup must be called before reading the controllers.")
+
+        return self.network.controllers
+
+    def melt_fractions(self) -> np.ndarray:
+        """
+        Liquid fraction of each node, NaN for the nodes without a phase change.
+
+        Returns:
+            np.ndarray: Melt fraction of each node in `nodes`, from 0 (solid) to 1 (liquid).
+
+        Raises:
+            RuntimeError: If the setup has not been completed.
+        """
+        if not self.ready:
+            raise RuntimeError("Setup must be called before reading the melt fractions.")
+
+        return self.network.melt_fractions
+
+    def stable_time_step(self) -> float:
+        """
+        Estimates the largest time step for which the explicit Euler scheme is stable
+        at the current temperatures.
+
+        Returns:
+            float: The maximum stable time step [s] (inf if nothing limits it).
+
+        Raises:
+            RuntimeError: If the setup has not been completed.
+        """
+        if not self.ready:
+            raise RuntimeError("Setup must be called before estimating the time step.")
+
+        self._push_state()
+        return self.network.stable_time_step()
+
+    def run_adaptive(self, total_time: float, atol: float = 1e-6, rtol: float = 1e-6) -> Dict[str, float]:
+        """
+        Runs the simulation for a specified time with adaptive time stepping (Dormand–Prince 5(4)).
+
+        Args:
+            total_time (float): Simulated time to run [s].
+            atol (float, optional): Absolute temperature tolerance [K]. Defaults to 1e-6.
+            rtol (float, optional): Relative temperature tolerance. Defaults to 1e-6.
+
+        Returns:
+            dict: Step statistics ('accepted_steps', 'rejected_steps', 'min_dt', 'max_dt').
+
+        Raises:
+            RuntimeError: If the setup has not been completed.
+            ChillValidationError: If total_time is negative or not finite, or if the network
+                has time-varying schedules, controllers or temperature-dependent properties,
+                which only `run` can follow.
+        """
+        if not self.ready:
+            raise RuntimeError("Setup must be called before running the simulation.")
+
+        self._push_state()
+        stats = self.network.run_adaptive(total_time, atol=atol, rtol=rtol)
+        self._sync_network()
+        return stats
+
+    def solve_steady_state(self, tol: float = 1e-8, max_iter: int = 100) -> Dict[str, float]:
+        """
+        Replaces the current temperatures with the equilibrium of the network.
+        Boundary nodes and nodes with infinite capacity are held at their temperature,
+        schedules are taken at the current time.
+
+        Args:
+            tol (float, optional): Residual net heat flow [W] at which the Newton iteration stops.
+                Defaults to 1e-8.
+            max_iter (int, optional): Maximum number of Newton iterations. Defaults to 100.
+
+        Returns:
+            dict: Convergence report ('iterations', 'residual_norm').
+
+        Raises:
+            RuntimeError: If the setup has not been completed.
+            ChillValidationError: If the network has controllers.
+        """
+        if not self.ready:
+            raise RuntimeError("Setup must be called before solving the steady state.")
+
+        self._push_state()
+        report = self.network.solve_steady_state(tol=tol, max_iter=max_iter)
+        self._sync_network()
+        return report
 
     def record_data(self) -> None:
         """
@@ -303,22 +744,47 @@ class Chill:
         self.temperatures_history.append(self.temperatures.copy())
         self.times_history.append(self.time)
 
-    def execute(self, total_time: float, record_interval: float = 0) -> None:
+    def execute(self, total_time: float, record_interval: float = 0, edge_flows: bool = False) -> None:
         """
         execute the simulation for a specified time.
         :param total_time: simulation time
         :param record_interval: recording interval
+        :param edge_flows: also record the heat flow through each edge in `edge_flows_history`
         """
         if record_interval == 0 or record_interval > total_time:
             self.run(steps=int(total_time / self.dt))
             return
 
+        if not self.ready:
+            raise RuntimeError("Setup must be called before running the simulation.")
+
         steps_per_interval = int(record_interval / self.dt)
         total_steps = int(total_time / self.dt)
         num_intervals = total_steps // steps_per_interval
-        for _ in tqdm(range(num_intervals)):
-            self.run(steps=steps_per_interval)
-            self.record_data()
+
+        # Step and record in chunks of intervals, one call to the core per progress update
+        chunks = min(num_intervals, self.PROGRESS_UPDATES)
+        self._push_state()
+        with tqdm(total=num_intervals) as progress:
+            for chunk in range(chunks):
+                intervals = num_intervals * (chunk + 1) // chunks - num_intervals * chunk // chunks
+                try:
+                    recording = self.network.record(
+                        intervals * steps_per_interval,
+                        record_every=steps_per_interval,
+                        edge_flows=edge_flows
+                    )
+                finally:
+                    self._sync_network()
+                times, temperatures = recording[:2]
+                self.temperatures_history.extend(temperatures)
+                if edge_flows:
+                    self.edge_flows_history.extend(recording[2])
+                self.times_history.extend(times.tolist())
+                progress.update(intervals)
+                if self.failure is not None:
+                    # on_failure='stop' ended the run early
+                    break
 
 
     def plot_top_temperature_changes(self, top_n: int = 5, figure_size: Optional[Tuple[int, int]] = (10, 6)) -> Figure:
@@ -0,0 +1,35 @@
+"""
+Exceptions raised by the chill core.
+
+They are defined here rather than in the extension module so that
+`ChillValidationError` can also be a `ValueError`.
+"""
+
+
+class ChillError(RuntimeError):
+    """
+    Base class of the errors raised by the chill core.
+
+    Every instance carries the attributes `node_index`, `edge_index`, `step`, `value` and
+    `time`, which are None when they do not apply. Errors that abort a run also carry the last
+    good `temperatures`.
+    """
+
+
+class ChillValidationError(ChillError, ValueError):
+    """
+    The network arrays or a value passed to a setter are invalid. `problems` lists every
+    problem found as (edge_index, node_index, value, message) tuples.
+    """
+
+
+class ChillEdgeTypeError(ChillValidationError):
+    """The network uses an undefined edge type."""
+
+
+class ChillInstabilityError(ChillError):
+    """The integration became numerically unstable."""
+
+
+class ChillSolverError(ChillError):
+    """An iterative solver failed to converge. Carries `iterations` and `residual`."""
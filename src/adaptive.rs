@@ -0,0 +1,217 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::edge::Edge;
+use crate::integrator::temperature_rates;
+
+// Dormand–Prince 5(4) tableau
+const A21: f64 = 1.0 / 5.0;
+const A31: f64 = 3.0 / 40.0;
+const A32: f64 = 9.0 / 40.0;
+const A41: f64 = 44.0 / 45.0;
+const A42: f64 = -56.0 / 15.0;
+const A43: f64 = 32.0 / 9.0;
+const A51: f64 = 19372.0 / 6561.0;
+const A52: f64 = -25360.0 / 2187.0;
+const A53: f64 = 64448.0 / 6561.0;
+const A54: f64 = -212.0 / 729.0;
+const A61: f64 = 9017.0 / 3168.0;
+const A62: f64 = -355.0 / 33.0;
+const A63: f64 = 46732.0 / 5247.0;
+const A64: f64 = 49.0 / 176.0;
+const A65: f64 = -5103.0 / 18656.0;
+const B1: f64 = 35.0 / 384.0;
+const B3: f64 = 500.0 / 1113.0;
+const B4: f64 = 125.0 / 192.0;
+const B5: f64 = -2187.0 / 6784.0;
+const B6: f64 = 11.0 / 84.0;
+// difference between the fifth and the embedded fourth order weights
+const E1: f64 = 71.0 / 57600.0;
+const E3: f64 = -71.0 / 16695.0;
+const E4: f64 = 71.0 / 1920.0;
+const E5: f64 = -17253.0 / 339200.0;
+const E6: f64 = 22.0 / 525.0;
+const E7: f64 = -1.0 / 40.0;
+
+const SAFETY: f64 = 0.9;
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 5.0;
+
+/// Statistics of an adaptive run.
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveStats {
+    pub accepted_steps: usize,
+    pub rejected_steps: usize,
+    pub min_dt: f64,
+    pub max_dt: f64,
+}
+
+impl AdaptiveStats {
+    /// The statistics as a dict with keys 'accepted_steps', 'rejected_steps',
+    /// 'min_dt' and 'max_dt'.
+    pub fn to_dict<'py>(self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let dict = PyDict::new(py);
+        dict.set_item("accepted_steps", self.accepted_steps)?;
+        dict.set_item("rejected_steps", self.rejected_steps)?;
+        dict.set_item("min_dt", self.min_dt)?;
+        dict.set_item("max_dt", self.max_dt)?;
+        Ok(dict)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum AdaptiveError {
+    /// The step size became negligible compared to the simulated time.
+    StepSizeUnderflow { time: f64, dt: f64 },
+    /// The step budget was exhausted before reaching the end time.
+    TooManySteps { time: f64, steps: usize },
+}
+
+impl std::fmt::Display for AdaptiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdaptiveError::StepSizeUnderflow { time, dt } => {
+                write!(f, "Step size underflow at t = {}: dt = {:e}", time, dt)
+            }
+            AdaptiveError::TooManySteps { time, steps } => {
+                write!(f, "Maximum number of steps ({}) reached at t = {}", steps, time)
+            }
+        }
+    }
+}
+
+/// Tolerance-scaled RMS norm of the local error estimate.
+fn error_norm(error: &[f64], old: &[f64], new: &[f64], atol: f64, rtol: f64) -> f64 {
+    if error.is_empty() {
+        return 0.0;
+    }
+    let sum: f64 = error
+        .iter()
+        .zip(old.iter().zip(new))
+        .map(|(e, (y0, y1))| {
+            let scale = atol + rtol * y0.abs().max(y1.abs());
+            (e / scale).powi(2)
+        })
+        .sum();
+    (sum / error.len() as f64).sqrt()
+}
+
+/// Integrate up to `t_end` with the Dormand–Prince 5(4) embedded pair.
+///
+/// The step size is adapted so that the local error estimate stays within
+/// `atol + rtol * |T|` for every node.
+///
+/// # Arguments
+///
+/// * `first_step` - Initial step size. Estimated from the initial rates if `None`.
+/// * `max_steps` - Maximum number of attempted steps.
+#[allow(clippy::too_many_arguments)]
+pub fn integrate_dopri5(
+    edges: &[Edge],
+    capacities: &[f64],
+    fixed: &[bool],
+    temperatures: &mut [f64],
+    t_end: f64,
+    atol: f64,
+    rtol: f64,
+    first_step: Option<f64>,
+    max_steps: usize,
+) -> Result<AdaptiveStats, AdaptiveError> {
+    let n = temperatures.len();
+    let mut stats = AdaptiveStats {
+        accepted_steps: 0,
+        rejected_steps: 0,
+        min_dt: f64::INFINITY,
+        max_dt: 0.0,
+    };
+    if t_end <= 0.0 {
+        return Ok(stats);
+    }
+
+    let mut k = vec![vec![0.0; n]; 7];
+    let mut stage = vec![0.0; n];
+    let mut next = vec![0.0; n];
+    let mut error = vec![0.0; n];
+
+    temperature_rates(edges, capacities, fixed, temperatures, &mut k[0]);
+
+    let mut dt = match first_step {
+        Some(dt) => dt,
+        None => {
+            let zeros = vec![0.0; n];
+            let d0 = error_norm(temperatures, &zeros, temperatures, atol, rtol);
+            let d1 = error_norm(&k[0], &zeros, temperatures, atol, rtol);
+            if d0 < 1e-5 || d1 < 1e-5 {
+                1e-6
+            } else {
+                0.01 * d0 / d1
+            }
+        }
+    }
+    .min(t_end);
+
+    let mut time = 0.0;
+    let mut rejected_last = false;
+    let mut attempts = 0;
+    while time < t_end {
+        if attempts == max_steps {
+            return Err(AdaptiveError::TooManySteps { time, steps: attempts });
+        }
+        attempts += 1;
+        let last = time + dt * (1.0 + 1e-12) >= t_end;
+        if last {
+            dt = t_end - time;
+        }
+        if dt <= 1e-14 * t_end.max(time) {
+            return Err(AdaptiveError::StepSizeUnderflow { time, dt });
+        }
+
+        let stages: [(&[f64], usize); 5] = [
+            (&[A21], 1),
+            (&[A31, A32], 2),
+            (&[A41, A42, A43], 3),
+            (&[A51, A52, A53, A54], 4),
+            (&[A61, A62, A63, A64, A65], 5),
+        ];
+        for (coefficients, target) in stages {
+            for i in 0..n {
+                let increment: f64 = coefficients.iter().zip(&k).map(|(a, ki)| a * ki[i]).sum();
+                stage[i] = temperatures[i] + dt * increment;
+            }
+            temperature_rates(edges, capacities, fixed, &stage, &mut k[target]);
+        }
+        for i in 0..n {
+            next[i] = temperatures[i]
+                + dt * (B1 * k[0][i] + B3 * k[2][i] + B4 * k[3][i] + B5 * k[4][i] + B6 * k[5][i]);
+        }
+        temperature_rates(edges, capacities, fixed, &next, &mut k[6]);
+        for i in 0..n {
+            error[i] = dt
+                * (E1 * k[0][i] + E3 * k[2][i] + E4 * k[3][i] + E5 * k[4][i] + E6 * k[5][i] + E7 * k[6][i]);
+        }
+
+        let err = error_norm(&error, temperatures, &next, atol, rtol);
+        if err <= 1.0 {
+            time = if last { t_end } else { time + dt };
+            temperatures.copy_from_slice(&next);
+            k.swap(0, 6); // first same as last
+            stats.accepted_steps += 1;
+            stats.min_dt = stats.min_dt.min(dt);
+            stats.max_dt = stats.max_dt.max(dt);
+
+            let mut factor = if err == 0.0 { MAX_FACTOR } else { SAFETY * err.powf(-0.2) };
+            factor = factor.clamp(MIN_FACTOR, MAX_FACTOR);
+            if rejected_last {
+                factor = factor.min(1.0);
+            }
+            dt *= factor;
+            rejected_last = false;
+        } else {
+            stats.rejected_steps += 1;
+            let factor = if err.is_finite() { SAFETY * err.powf(-0.2) } else { MIN_FACTOR };
+            dt *= factor.max(MIN_FACTOR);
+            rejected_last = true;
+        }
+    }
+    Ok(stats)
+}
@@ -0,0 +1,376 @@
+// The pyo3 0.16 #[pymethods] expansion trips this rustc lint.
+#![allow(non_local_definitions)]
+
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::errors::{invalid_value, ErrorInfo};
+
+/// Bang-bang heater with hysteresis, as used for survival heaters.
+///
+/// The heater drives the power of a HeatInput edge into its target node: it
+/// switches on when the sensor node cools down to `on_temperature` and off
+/// when it warms up to `off_temperature`. The state is evaluated at the start
+/// of every step from the sensor temperature.
+///
+/// Parameters
+/// ----------
+/// edge : int
+///     Index of the HeatInput edge of the heater; its second node is the
+///     heated target node.
+/// sensor : int
+///     Index of the node whose temperature is sensed.
+/// power : float
+///     Heater power while it is on [W].
+/// on_temperature : float
+///     The heater switches on at or below this temperature [K].
+/// off_temperature : float
+///     The heater switches off at or above this temperature [K].
+#[pyclass(module = "chill")]
+#[derive(Debug, Clone)]
+pub struct Thermostat {
+    #[pyo3(get)]
+    edge: usize,
+    #[pyo3(get)]
+    sensor: usize,
+    #[pyo3(get)]
+    power: f64,
+    #[pyo3(get)]
+    on_temperature: f64,
+    #[pyo3(get)]
+    off_temperature: f64,
+    on: bool,
+    on_time: f64,
+    elapsed: f64,
+    /// (time, on) of every switching.
+    switches: Vec<(f64, bool)>,
+}
+
+impl Thermostat {
+    /// Switch on the sensor temperature at `time` and return the heater power [W].
+    fn update(&mut self, time: f64, temperatures: &[f64]) -> f64 {
+        let reading = temperatures[self.sensor];
+        let switch = if self.on {
+            reading >= self.off_temperature
+        } else {
+            reading <= self.on_temperature
+        };
+        if switch {
+            self.on = !self.on;
+            self.switches.push((time, self.on));
+        }
+        if self.on {
+            self.power
+        } else {
+            0.0
+        }
+    }
+
+    fn account(&mut self, dt: f64) {
+        self.elapsed += dt;
+        if self.on {
+            self.on_time += dt;
+        }
+    }
+
+    /// Fraction of the simulated time the heater was on.
+    fn duty_cycle(&self) -> f64 {
+        if self.elapsed > 0.0 {
+            self.on_time / self.elapsed
+        } else {
+            0.0
+        }
+    }
+
+    fn fill_report(&self, report: &PyDict) -> PyResult<()> {
+        report.set_item("on", self.on)?;
+        report.set_item("duty_cycle", self.duty_cycle())?;
+        report.set_item("on_time", self.on_time)?;
+        let (times, states): (Vec<f64>, Vec<bool>) = self.switches.iter().copied().unzip();
+        report.set_item("switch_times", times)?;
+        report.set_item("switch_states", states)?;
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl Thermostat {
+    #[new]
+    fn new(edge: usize, sensor: usize, power: f64, on_temperature: f64, off_temperature: f64) -> PyResult<Self> {
+        if !power.is_finite() || power < 0.0 {
+            return Err(invalid_value(
+                format!("Heater power must be finite and not negative, got {}", power),
+                ErrorInfo {
+                    edge_index: Some(edge),
+                    value: Some(power),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        if on_temperature.is_nan() || off_temperature.is_nan() || on_temperature >= off_temperature {
+            return Err(invalid_value(
+                format!(
+                    "on_temperature ({}) must be below off_temperature ({})",
+                    on_temperature, off_temperature
+                ),
+                ErrorInfo {
+                    edge_index: Some(edge),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        Ok(Thermostat {
+            edge,
+            sensor,
+            power,
+            on_temperature,
+            off_temperature,
+            on: false,
+            on_time: 0.0,
+            elapsed: 0.0,
+            switches: Vec::new(),
+        })
+    }
+}
+
+/// Heater power set by a PID controller on the temperature of a sensor node.
+///
+/// The controller drives the power of a HeatInput edge into its target node.
+/// It samples the sensor every `period` of simulated time and holds its
+/// output in between; the output is clamped to [`min_power`, `max_power`],
+/// and the integral stops accumulating while the output is saturated in the
+/// direction of the error (anti-windup). The derivative acts on the
+/// measurement, so changing the set point does not kick the output.
+///
+/// Parameters
+/// ----------
+/// edge : int
+///     Index of the HeatInput edge of the heater; its second node is the
+///     heated target node.
+/// sensor : int
+///     Index of the node whose temperature is controlled.
+/// setpoint : float
+///     Target temperature of the sensor node [K].
+/// kp : float
+///     Proportional gain [W/K].
+/// ki : float, optional
+///     Integral gain [W/(K s)]. Default is 0.
+/// kd : float, optional
+///     Derivative gain [W s/K]. Default is 0.
+/// min_power : float, optional
+///     Lower output limit [W]. Default is 0 (a heater cannot cool).
+/// max_power : float, optional
+///     Upper output limit [W]. Default is inf.
+/// period : float, optional
+///     Control period [s]. A period shorter than the time step acts at every
+///     step. Default is 0.
+#[pyclass(module = "chill")]
+#[derive(Debug, Clone)]
+pub struct PidController {
+    #[pyo3(get)]
+    edge: usize,
+    #[pyo3(get)]
+    sensor: usize,
+    #[pyo3(get)]
+    setpoint: f64,
+    #[pyo3(get)]
+    kp: f64,
+    #[pyo3(get)]
+    ki: f64,
+    #[pyo3(get)]
+    kd: f64,
+    #[pyo3(get)]
+    min_power: f64,
+    #[pyo3(get)]
+    max_power: f64,
+    #[pyo3(get)]
+    period: f64,
+    integral: f64,
+    error: f64,
+    power: f64,
+    saturated: bool,
+    /// Time and sensor temperature of the last sample.
+    last_sample: Option<(f64, f64)>,
+    samples: usize,
+}
+
+impl PidController {
+    /// Sample the sensor if a control period has passed and return the power [W].
+    fn update(&mut self, time: f64, temperatures: &[f64]) -> f64 {
+        let reading = temperatures[self.sensor];
+        let elapsed = match self.last_sample {
+            // evaluations at the same instant must not integrate twice
+            Some((last, _)) if time <= last || time - last < self.period * (1.0 - 1e-9) => return self.power,
+            Some((last, _)) => time - last,
+            None => 0.0,
+        };
+        let error = self.setpoint - reading;
+        let derivative = match self.last_sample {
+            Some((_, previous)) => -(reading - previous) / elapsed,
+            None => 0.0,
+        };
+        let output = |integral: f64| self.kp * error + self.ki * integral + self.kd * derivative;
+        let mut integral = self.integral + error * elapsed;
+        let mut power = output(integral);
+        // anti-windup: do not integrate further into the saturation
+        if (power > self.max_power && error > 0.0) || (power < self.min_power && error < 0.0) {
+            integral = self.integral;
+            power = output(integral);
+        }
+        self.integral = integral;
+        self.saturated = power > self.max_power || power < self.min_power;
+        self.power = power.clamp(self.min_power, self.max_power);
+        self.error = error;
+        self.last_sample = Some((time, reading));
+        self.samples += 1;
+        self.power
+    }
+
+    fn fill_report(&self, report: &PyDict) -> PyResult<()> {
+        report.set_item("setpoint", self.setpoint)?;
+        report.set_item("power", self.power)?;
+        report.set_item("error", self.error)?;
+        report.set_item("integral", self.integral)?;
+        report.set_item("saturated", self.saturated)?;
+        report.set_item("samples", self.samples)?;
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl PidController {
+    #[new]
+    #[args(ki = "0.0", kd = "0.0", min_power = "0.0", max_power = "f64::INFINITY", period = "0.0")]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        edge: usize,
+        sensor: usize,
+        setpoint: f64,
+        kp: f64,
+        ki: f64,
+        kd: f64,
+        min_power: f64,
+        max_power: f64,
+        period: f64,
+    ) -> PyResult<Self> {
+        if [setpoint, kp, ki, kd].iter().any(|x| !x.is_finite()) {
+            return Err(invalid_value(
+                "PID set point and gains must be finite".to_string(),
+                ErrorInfo {
+                    edge_index: Some(edge),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        if !min_power.is_finite() || max_power.is_nan() || min_power > max_power {
+            return Err(invalid_value(
+                format!("Invalid power limits ({}, {})", min_power, max_power),
+                ErrorInfo {
+                    edge_index: Some(edge),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        if !period.is_finite() || period < 0.0 {
+            return Err(invalid_value(
+                format!("Control period must be finite and not negative, got {}", period),
+                ErrorInfo {
+                    edge_index: Some(edge),
+                    value: Some(period),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        Ok(PidController {
+            edge,
+            sensor,
+            setpoint,
+            kp,
+            ki,
+            kd,
+            min_power,
+            max_power,
+            period,
+            integral: 0.0,
+            error: 0.0,
+            power: min_power,
+            saturated: false,
+            last_sample: None,
+            samples: 0,
+        })
+    }
+}
+
+/// A control element driving the power of a HeatInput edge.
+#[derive(Debug, Clone)]
+pub enum Controller {
+    Thermostat(Thermostat),
+    Pid(PidController),
+}
+
+impl Controller {
+    /// A `Thermostat` or a `PidController`.
+    pub fn extract(value: &PyAny) -> PyResult<Self> {
+        if let Ok(thermostat) = value.extract::<PyRef<Thermostat>>() {
+            return Ok(Controller::Thermostat(thermostat.clone()));
+        }
+        if let Ok(pid) = value.extract::<PyRef<PidController>>() {
+            return Ok(Controller::Pid(pid.clone()));
+        }
+        Err(PyTypeError::new_err(format!(
+            "Expected a Thermostat or a PidController, got {}",
+            value.get_type().name()?
+        )))
+    }
+
+    /// Index of the HeatInput edge driven by the controller.
+    pub fn edge(&self) -> usize {
+        match self {
+            Controller::Thermostat(thermostat) => thermostat.edge,
+            Controller::Pid(pid) => pid.edge,
+        }
+    }
+
+    /// Index of the node read by the controller.
+    pub fn sensor(&self) -> usize {
+        match self {
+            Controller::Thermostat(thermostat) => thermostat.sensor,
+            Controller::Pid(pid) => pid.sensor,
+        }
+    }
+
+    /// Evaluate the controller at `time` and return the power of its edge [W].
+    pub fn update(&mut self, time: f64, temperatures: &[f64]) -> f64 {
+        match self {
+            Controller::Thermostat(thermostat) => thermostat.update(time, temperatures),
+            Controller::Pid(pid) => pid.update(time, temperatures),
+        }
+    }
+
+    /// Account for a completed step of `dt`.
+    pub fn account(&mut self, dt: f64) {
+        match self {
+            Controller::Thermostat(thermostat) => thermostat.account(dt),
+            Controller::Pid(_) => {}
+        }
+    }
+
+    /// Report as a dict with 'type', 'edge', 'sensor' and the state of the controller.
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let report = PyDict::new(py);
+        report.set_item("edge", self.edge())?;
+        report.set_item("sensor", self.sensor())?;
+        match self {
+            Controller::Thermostat(thermostat) => {
+                report.set_item("type", "thermostat")?;
+                thermostat.fill_report(report)?;
+            }
+            Controller::Pid(pid) => {
+                report.set_item("type", "pid")?;
+                pid.fill_report(report)?;
+            }
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,277 @@
+// The pyo3 0.16 #[pymethods] expansion trips this rustc lint.
+#![allow(non_local_definitions)]
+
+use pyo3::prelude::*;
+
+use crate::edge::NATURAL_CONVECTION_EXPONENT;
+use crate::errors::{invalid_value, ErrorInfo};
+use crate::profile::{Interpolation, Profile, Schedule};
+
+/// Standard gravity [m/s^2].
+const GRAVITY: f64 = 9.80665;
+
+/// Reynolds number at which the boundary layer on a plate turns turbulent.
+const CRITICAL_REYNOLDS: f64 = 5e5;
+
+/// Properties of the fluid, evaluated at the film temperature.
+///
+/// Each property is a constant, a (temperatures, values) table, a `Profile`
+/// over temperature or a function of temperature.
+///
+/// Parameters
+/// ----------
+/// conductivity : float, (ndarray, ndarray), Profile or callable
+///     Thermal conductivity k [W/(m K)].
+/// viscosity : float, (ndarray, ndarray), Profile or callable
+///     Kinematic viscosity ν [m^2/s].
+/// prandtl : float, (ndarray, ndarray), Profile or callable
+///     Prandtl number.
+/// expansion : float, (ndarray, ndarray), Profile or callable, optional
+///     Volumetric thermal expansion coefficient β [1/K]. Default is None,
+///     which takes 1 / T as for an ideal gas.
+#[pyclass(module = "chill")]
+#[derive(Clone)]
+pub struct Fluid {
+    conductivity: Schedule,
+    viscosity: Schedule,
+    prandtl: Schedule,
+    expansion: Option<Schedule>,
+}
+
+/// Fluid properties at one temperature.
+struct FluidState {
+    conductivity: f64,
+    viscosity: f64,
+    prandtl: f64,
+    expansion: f64,
+}
+
+impl Fluid {
+    fn state(&self, temperature: f64) -> PyResult<FluidState> {
+        let state = FluidState {
+            conductivity: self.conductivity.value_at(temperature)?,
+            viscosity: self.viscosity.value_at(temperature)?,
+            prandtl: self.prandtl.value_at(temperature)?,
+            expansion: match &self.expansion {
+                Some(expansion) => expansion.value_at(temperature)?,
+                None => 1.0 / temperature,
+            },
+        };
+        if [state.conductivity, state.viscosity, state.prandtl, state.expansion]
+            .iter()
+            .any(|&x| x.is_nan() || x <= 0.0)
+        {
+            return Err(invalid_value(
+                format!(
+                    "Fluid properties must be positive, got k = {}, nu = {}, Pr = {}, beta = {} at {} K",
+                    state.conductivity, state.viscosity, state.prandtl, state.expansion, temperature
+                ),
+                ErrorInfo::default(),
+            ));
+        }
+        Ok(state)
+    }
+}
+
+#[pymethods]
+impl Fluid {
+    #[new]
+    #[args(expansion = "None")]
+    fn new(conductivity: &PyAny, viscosity: &PyAny, prandtl: &PyAny, expansion: Option<&PyAny>) -> PyResult<Self> {
+        Ok(Fluid {
+            conductivity: Schedule::extract(conductivity)?,
+            viscosity: Schedule::extract(viscosity)?,
+            prandtl: Schedule::extract(prandtl)?,
+            expansion: expansion.map(Schedule::extract).transpose()?,
+        })
+    }
+
+    /// Dry air at atmospheric pressure, tabulated from 200 K to 500 K.
+    #[staticmethod]
+    fn air() -> PyResult<Self> {
+        let temperatures = vec![200.0, 250.0, 300.0, 350.0, 400.0, 500.0];
+        let table = |values: Vec<f64>| -> PyResult<Schedule> {
+            Profile::build(temperatures.clone(), values, Interpolation::Linear, None).map(Schedule::Table)
+        };
+        Ok(Fluid {
+            conductivity: table(vec![0.0181, 0.0223, 0.0263, 0.0300, 0.0338, 0.0407])?,
+            viscosity: table(vec![7.59e-6, 11.44e-6, 15.89e-6, 20.92e-6, 26.41e-6, 38.79e-6])?,
+            prandtl: table(vec![0.737, 0.720, 0.707, 0.700, 0.690, 0.684])?,
+            expansion: None,
+        })
+    }
+}
+
+/// How the heat transfer coefficient is obtained.
+#[derive(Clone)]
+enum Correlation {
+    /// h = coefficient * |ΔT|^exponent.
+    PowerLaw { coefficient: f64, exponent: f64 },
+    /// Natural convection on a vertical plate (Churchill–Chu).
+    VerticalPlate { height: f64, fluid: Fluid },
+    /// Natural convection around a horizontal cylinder (Churchill–Chu).
+    HorizontalCylinder { diameter: f64, fluid: Fluid },
+    /// Forced flow along a flat plate, laminar or mixed boundary layer.
+    ForcedPlate { length: f64, velocity: f64, fluid: Fluid },
+}
+
+/// Heat transfer coefficient of a Convection edge.
+///
+/// Build one with `power_law`, `vertical_plate`, `horizontal_cylinder` or
+/// `forced_plate`. The correlations evaluate the fluid properties at the film
+/// temperature, the mean of the two node temperatures, and are re-evaluated
+/// at every step.
+#[pyclass(module = "chill")]
+#[derive(Clone)]
+pub struct Convection {
+    correlation: Correlation,
+    /// Wetted area [m^2].
+    area: f64,
+}
+
+/// `ChillValidationError` for `value` of `name`, which must meet `requirement`.
+fn invalid_argument(name: &str, value: f64, requirement: &str) -> PyErr {
+    invalid_value(
+        format!("{} must {}, got {}", name, requirement, value),
+        ErrorInfo {
+            value: Some(value),
+            ..ErrorInfo::default()
+        },
+    )
+}
+
+fn check_positive(name: &str, value: f64) -> PyResult<()> {
+    if !value.is_finite() || value <= 0.0 {
+        return Err(invalid_argument(name, value, "be positive"));
+    }
+    Ok(())
+}
+
+fn check_not_negative(name: &str, value: f64) -> PyResult<()> {
+    if !value.is_finite() || value < 0.0 {
+        return Err(invalid_argument(name, value, "not be negative"));
+    }
+    Ok(())
+}
+
+/// Nusselt number of the Churchill–Chu correlations for natural convection.
+fn churchill_chu(rayleigh: f64, prandtl: f64, offset: f64, prandtl_scale: f64) -> f64 {
+    let shape = (1.0 + (prandtl_scale / prandtl).powf(9.0 / 16.0)).powf(8.0 / 27.0);
+    (offset + 0.387 * rayleigh.powf(1.0 / 6.0) / shape).powi(2)
+}
+
+impl Convection {
+    /// Exponent of |ΔT| in the edge's law: that of the power law, 0 for the
+    /// correlations, whose coefficient is updated every step instead.
+    pub fn exponent(&self) -> f64 {
+        match self.correlation {
+            Correlation::PowerLaw { exponent, .. } => exponent,
+            _ => 0.0,
+        }
+    }
+
+    /// Whether the edge parameter depends on the temperatures.
+    pub fn is_constant(&self) -> bool {
+        matches!(self.correlation, Correlation::PowerLaw { .. })
+    }
+
+    /// Heat transfer coefficient h [W/(m^2 K)] between nodes at `t1` and `t2`,
+    /// without the |ΔT|^n factor of the power law.
+    fn coefficient(&self, t1: f64, t2: f64) -> PyResult<f64> {
+        let film = 0.5 * (t1 + t2);
+        let delta = (t1 - t2).abs();
+        let natural = |length: f64, fluid: &Fluid, offset: f64, prandtl_scale: f64| -> PyResult<f64> {
+            let state = fluid.state(film)?;
+            let rayleigh =
+                GRAVITY * state.expansion * delta * length.powi(3) / state.viscosity.powi(2) * state.prandtl;
+            let nusselt = churchill_chu(rayleigh, state.prandtl, offset, prandtl_scale);
+            Ok(nusselt * state.conductivity / length)
+        };
+        match &self.correlation {
+            Correlation::PowerLaw { coefficient, .. } => Ok(*coefficient),
+            Correlation::VerticalPlate { height, fluid } => natural(*height, fluid, 0.825, 0.492),
+            Correlation::HorizontalCylinder { diameter, fluid } => natural(*diameter, fluid, 0.60, 0.559),
+            Correlation::ForcedPlate { length, velocity, fluid } => {
+                let state = fluid.state(film)?;
+                let reynolds = velocity * length / state.viscosity;
+                let nusselt = if reynolds < CRITICAL_REYNOLDS {
+                    0.664 * reynolds.sqrt() * state.prandtl.cbrt()
+                } else {
+                    (0.037 * reynolds.powf(0.8) - 871.0) * state.prandtl.cbrt()
+                };
+                Ok(nusselt * state.conductivity / length)
+            }
+        }
+    }
+
+    /// Edge parameter between nodes at `t1` and `t2`: coefficient times area.
+    pub fn parameter(&self, t1: f64, t2: f64) -> PyResult<f64> {
+        Ok(self.coefficient(t1, t2)? * self.area)
+    }
+}
+
+#[pymethods]
+impl Convection {
+    /// h = coefficient * |ΔT|^exponent [W/(m^2 K)].
+    ///
+    /// The default exponent 0.25 is laminar natural convection, where the
+    /// heat flow grows like ΔT^1.25.
+    #[staticmethod]
+    #[args(exponent = "NATURAL_CONVECTION_EXPONENT", area = "1.0")]
+    fn power_law(coefficient: f64, exponent: f64, area: f64) -> PyResult<Self> {
+        check_not_negative("coefficient", coefficient)?;
+        check_not_negative("exponent", exponent)?;
+        check_positive("area", area)?;
+        Ok(Convection {
+            correlation: Correlation::PowerLaw { coefficient, exponent },
+            area,
+        })
+    }
+
+    /// Natural convection on a vertical plate of `height` [m] and `area` [m^2].
+    #[staticmethod]
+    fn vertical_plate(height: f64, area: f64, fluid: Fluid) -> PyResult<Self> {
+        check_positive("height", height)?;
+        check_positive("area", area)?;
+        Ok(Convection {
+            correlation: Correlation::VerticalPlate { height, fluid },
+            area,
+        })
+    }
+
+    /// Natural convection around a horizontal cylinder of `diameter` [m] and `area` [m^2].
+    #[staticmethod]
+    fn horizontal_cylinder(diameter: f64, area: f64, fluid: Fluid) -> PyResult<Self> {
+        check_positive("diameter", diameter)?;
+        check_positive("area", area)?;
+        Ok(Convection {
+            correlation: Correlation::HorizontalCylinder { diameter, fluid },
+            area,
+        })
+    }
+
+    /// Forced flow at `velocity` [m/s] along a flat plate of `length` [m] in
+    /// the flow direction and `area` [m^2].
+    #[staticmethod]
+    fn forced_plate(length: f64, area: f64, velocity: f64, fluid: Fluid) -> PyResult<Self> {
+        check_positive("length", length)?;
+        check_positive("area", area)?;
+        check_positive("velocity", velocity)?;
+        Ok(Convection {
+            correlation: Correlation::ForcedPlate { length, velocity, fluid },
+            area,
+        })
+    }
+
+    /// Heat transfer coefficient h [W/(m^2 K)] between surfaces at `t1` and `t2`.
+    #[pyo3(name = "coefficient")]
+    fn py_coefficient(&self, t1: f64, t2: f64) -> PyResult<f64> {
+        Ok(self.coefficient(t1, t2)? * (t1 - t2).abs().powf(self.exponent()))
+    }
+
+    /// Parameter of the Convection edge between nodes at `t1` and `t2`.
+    #[pyo3(name = "parameter")]
+    fn py_parameter(&self, t1: f64, t2: f64) -> PyResult<f64> {
+        self.parameter(t1, t2)
+    }
+}
@@ -0,0 +1,118 @@
+/// Exponent n of h ∝ |ΔT|^n for laminar natural convection.
+pub const NATURAL_CONVECTION_EXPONENT: f64 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EdgeType {
+    Transfer,
+    Radiation,
+    HeatInput,
+    /// Heat transfer coefficient h = C * |ΔT|^exponent.
+    Convection { exponent: f64 },
+    /// Heat carried by a fluid flowing from the first node to the second.
+    Advection,
+}
+
+impl EdgeType {
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(EdgeType::Transfer),
+            1 => Some(EdgeType::Radiation),
+            2 => Some(EdgeType::HeatInput),
+            3 => Some(EdgeType::Convection {
+                exponent: NATURAL_CONVECTION_EXPONENT,
+            }),
+            4 => Some(EdgeType::Advection),
+            _ => None,
+        }
+    }
+
+    /// Heat flow from the first node to the second node [W].
+    ///
+    /// # Arguments
+    ///
+    /// * `parameter` - The parameter associated with the edge type.
+    /// * `t1` - Temperature of the first node.
+    /// * `t2` - Temperature of the second node.
+    pub fn heat_flow(self, parameter: f64, t1: f64, t2: f64) -> f64 {
+        match self {
+            // parameter is heat resistance
+            EdgeType::Transfer => (t1 - t2) / parameter,
+            // parameter is ε * E_G * σ * A
+            EdgeType::Radiation => (t1.powi(4) - t2.powi(4)) * parameter,
+            // parameter is Q [W] (heat input to n2)
+            EdgeType::HeatInput => parameter,
+            // parameter is C * A [W/K^(1+n)]
+            EdgeType::Convection { exponent } => parameter * (t1 - t2).abs().powf(exponent) * (t1 - t2),
+            // parameter is ṁ * cp [W/K] (heat carried into n2)
+            EdgeType::Advection => parameter * (t1 - t2),
+        }
+    }
+
+    /// Partial derivatives of `heat_flow` with respect to `t1` and `t2`.
+    ///
+    /// Radiation is linearized around the given temperatures.
+    pub fn heat_flow_derivatives(self, parameter: f64, t1: f64, t2: f64) -> (f64, f64) {
+        match self {
+            EdgeType::Transfer => (1.0 / parameter, -1.0 / parameter),
+            EdgeType::Radiation => (4.0 * parameter * t1.powi(3), -4.0 * parameter * t2.powi(3)),
+            EdgeType::HeatInput => (0.0, 0.0),
+            EdgeType::Convection { exponent } => {
+                let conductance = (exponent + 1.0) * parameter * (t1 - t2).abs().powf(exponent);
+                (conductance, -conductance)
+            }
+            EdgeType::Advection => (parameter, -parameter),
+        }
+    }
+
+    /// Whether the first node loses the heat the second node gains.
+    ///
+    /// A heat input only feeds the second node, and so does an advection
+    /// edge: the fluid leaving the first node is accounted for by the edge
+    /// that brought it in.
+    pub fn is_exchange(self) -> bool {
+        match self {
+            EdgeType::Transfer | EdgeType::Radiation | EdgeType::Convection { .. } => true,
+            EdgeType::HeatInput | EdgeType::Advection => false,
+        }
+    }
+}
+
+/// A single edge of the network with its edge type already decoded.
+#[derive(Debug, Clone, Copy)]
+pub struct Edge {
+    pub edge_type: EdgeType,
+    pub parameter: f64,
+    pub n1: usize,
+    pub n2: usize,
+}
+
+impl Edge {
+    pub fn heat_flow(&self, temperatures: &[f64]) -> f64 {
+        self.edge_type
+            .heat_flow(self.parameter, temperatures[self.n1], temperatures[self.n2])
+    }
+
+    pub fn heat_flow_derivatives(&self, temperatures: &[f64]) -> (f64, f64) {
+        self.edge_type
+            .heat_flow_derivatives(self.parameter, temperatures[self.n1], temperatures[self.n2])
+    }
+}
+
+/// Net heat flow into each node [W].
+pub fn heat_balance(edges: &[Edge], temperatures: &[f64], flows: &mut [f64]) {
+    flows.iter_mut().for_each(|f| *f = 0.0);
+    for edge in edges {
+        let q = edge.heat_flow(temperatures);
+        if edge.edge_type.is_exchange() {
+            flows[edge.n1] -= q;
+        }
+        flows[edge.n2] += q;
+    }
+}
+
+/// Heat flow through each edge from n1 to n2 [W].
+pub fn edge_flows(edges: &[Edge], temperatures: &[f64], flows: &mut [f64]) {
+    for (flow, edge) in flows.iter_mut().zip(edges) {
+        *flow = edge.heat_flow(temperatures);
+    }
+}
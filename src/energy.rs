@@ -0,0 +1,137 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::edge::{Edge, EdgeType};
+
+/// Power entering the free nodes at the given temperatures [W].
+///
+/// Returns the power of the HeatInput edges and the net power exchanged with
+/// the fixed nodes (boundaries and nodes of infinite capacity). Advection
+/// edges count as boundary power: the enthalpy a fluid brings into a free
+/// node minus what it carries on, which cancels around a closed loop.
+pub fn external_power(edges: &[Edge], fixed: &[bool], temperatures: &[f64]) -> (f64, f64) {
+    let mut heat_input = 0.0;
+    let mut boundary = 0.0;
+    for edge in edges {
+        let fixed1 = fixed[edge.n1];
+        let fixed2 = fixed[edge.n2];
+        match (edge.edge_type, fixed1, fixed2) {
+            // heat fed into a fixed node never reaches the free nodes
+            (EdgeType::HeatInput, _, false) => heat_input += edge.heat_flow(temperatures),
+            (EdgeType::HeatInput, _, true) => {}
+            (EdgeType::Advection, _, false) => boundary += edge.heat_flow(temperatures),
+            (EdgeType::Advection, _, true) => {}
+            (_, true, false) => boundary += edge.heat_flow(temperatures),
+            (_, false, true) => boundary -= edge.heat_flow(temperatures),
+            _ => {}
+        }
+    }
+    (heat_input, boundary)
+}
+
+/// Energy stored in the free nodes relative to 0 K [J].
+pub fn stored_energy(capacities: &[f64], fixed: &[bool], temperatures: &[f64]) -> f64 {
+    capacities
+        .iter()
+        .zip(temperatures)
+        .zip(fixed)
+        .filter(|(_, &fixed)| !fixed)
+        .map(|((c, t), _)| c * t)
+        .sum()
+}
+
+/// Energy bookkeeping of the free nodes over a run.
+///
+/// The energy crossing the network boundary in each step is integrated with
+/// the trapezoidal rule, so the balance error measures the time
+/// discretization error of the scheme and vanishes as dt goes to zero.
+///
+/// Nodes with a temperature-dependent capacity do not store C * T; their
+/// enthalpy change is accumulated step by step instead.
+#[derive(Debug, Clone)]
+pub struct EnergyBalance {
+    /// Energy injected by HeatInput edges [J].
+    pub heat_input: f64,
+    /// Energy received from fixed nodes [J].
+    pub boundary: f64,
+    initial_stored: f64,
+    /// (heat input, boundary) power at the start of the next step [W].
+    power: (f64, f64),
+    /// Nodes held out of the integration.
+    fixed: Vec<bool>,
+    /// Nodes with a temperature-dependent capacity.
+    enthalpy_nodes: Vec<usize>,
+    /// Enthalpy change of those nodes [J].
+    enthalpy: f64,
+}
+
+impl EnergyBalance {
+    pub fn new(
+        edges: &[Edge],
+        capacities: &[f64],
+        fixed: &[bool],
+        temperatures: &[f64],
+        enthalpy_nodes: Vec<usize>,
+    ) -> Self {
+        let mut balance = EnergyBalance {
+            heat_input: 0.0,
+            boundary: 0.0,
+            initial_stored: 0.0,
+            power: external_power(edges, fixed, temperatures),
+            fixed: fixed.to_vec(),
+            enthalpy_nodes,
+            enthalpy: 0.0,
+        };
+        balance.initial_stored = balance.sensible(capacities, temperatures);
+        balance
+    }
+
+    /// Energy stored as C * T by the nodes of constant capacity [J].
+    fn sensible(&self, capacities: &[f64], temperatures: &[f64]) -> f64 {
+        let excluded: f64 = self
+            .enthalpy_nodes
+            .iter()
+            .map(|&node| capacities[node] * temperatures[node])
+            .sum();
+        stored_energy(capacities, &self.fixed, temperatures) - excluded
+    }
+
+    /// Account for the enthalpy change of the nodes with a
+    /// temperature-dependent capacity over a step [J].
+    pub fn record_enthalpy(&mut self, enthalpy: f64) {
+        self.enthalpy += enthalpy;
+    }
+
+    /// Account for a step of `dt` that ended at `temperatures`.
+    pub fn record_step(&mut self, edges: &[Edge], temperatures: &[f64], dt: f64) {
+        let (heat_input, boundary) = external_power(edges, &self.fixed, temperatures);
+        self.heat_input += 0.5 * dt * (self.power.0 + heat_input);
+        self.boundary += 0.5 * dt * (self.power.1 + boundary);
+        self.power = (heat_input, boundary);
+    }
+
+    /// Re-evaluate the power at the start of the next step after the edges changed.
+    pub fn refresh(&mut self, edges: &[Edge], temperatures: &[f64]) {
+        self.power = external_power(edges, &self.fixed, temperatures);
+    }
+
+    /// Change of the energy stored in the free nodes since the start [J].
+    pub fn stored(&self, capacities: &[f64], temperatures: &[f64]) -> f64 {
+        self.sensible(capacities, temperatures) - self.initial_stored + self.enthalpy
+    }
+
+    /// Stored energy change minus the energy that entered [J].
+    pub fn error(&self, capacities: &[f64], temperatures: &[f64]) -> f64 {
+        self.stored(capacities, temperatures) - self.heat_input - self.boundary
+    }
+
+    /// Report as a dict with 'heat_input', 'boundary', 'stored' and 'error' [J].
+    pub fn to_dict<'py>(&self, py: Python<'py>, capacities: &[f64], temperatures: &[f64]) -> PyResult<&'py PyDict> {
+        let dict = PyDict::new(py);
+        dict.set_item("heat_input", self.heat_input)?;
+        dict.set_item("boundary", self.boundary)?;
+        dict.set_item("stored", self.stored(capacities, temperatures))?;
+        dict.set_item("error", self.error(capacities, temperatures))?;
+        Ok(dict)
+    }
+}
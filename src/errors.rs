@@ -0,0 +1,246 @@
+// pyo3 0.16 import_exception! expands to a cfg unknown to current rustc.
+#![allow(unexpected_cfgs)]
+
+use numpy::PyArray1;
+use pyo3::import_exception;
+use pyo3::prelude::*;
+use pyo3::type_object::PyTypeObject;
+
+use crate::adaptive::AdaptiveError;
+use crate::guard::Violation;
+use crate::sparse::SolveError;
+use crate::steady::SteadyStateError;
+use crate::validation::{Problem, ProblemKind};
+
+// Defined in chill/errors.py, where ChillValidationError can also derive
+// from ValueError.
+import_exception!(chill.errors, ChillError);
+import_exception!(chill.errors, ChillValidationError);
+import_exception!(chill.errors, ChillEdgeTypeError);
+import_exception!(chill.errors, ChillInstabilityError);
+import_exception!(chill.errors, ChillSolverError);
+
+/// (edge_index, node_index, value, message) as exposed in `problems`.
+type ProblemTuple = (Option<usize>, Option<usize>, Option<f64>, String);
+
+/// Structured context attached to a chill exception.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ErrorInfo {
+    pub node_index: Option<usize>,
+    pub edge_index: Option<usize>,
+    pub step: Option<usize>,
+    pub value: Option<f64>,
+    pub time: Option<f64>,
+}
+
+fn new_error<T: PyTypeObject>(
+    message: String,
+    info: ErrorInfo,
+    extra: impl FnOnce(&PyAny) -> PyResult<()>,
+) -> PyErr {
+    Python::with_gil(|py| {
+        let err = PyErr::new::<T, _>(message);
+        let instance = err.value(py);
+        let attached = instance
+            .setattr("node_index", info.node_index)
+            .and_then(|_| instance.setattr("edge_index", info.edge_index))
+            .and_then(|_| instance.setattr("step", info.step))
+            .and_then(|_| instance.setattr("value", info.value))
+            .and_then(|_| instance.setattr("time", info.time))
+            .and_then(|_| extra(instance));
+        match attached {
+            Ok(()) => err,
+            Err(e) => e,
+        }
+    })
+}
+
+/// `ChillValidationError` (or `ChillEdgeTypeError` if an edge type is
+/// undefined) listing every problem found in the network.
+pub fn validation_error(problems: &[Problem]) -> PyErr {
+    let lines: Vec<String> = problems.iter().map(|p| format!("  {}", p)).collect();
+    let message = format!(
+        "Invalid network ({} problem{}):\n{}",
+        problems.len(),
+        if problems.len() == 1 { "" } else { "s" },
+        lines.join("\n")
+    );
+    let first = problems.first();
+    let info = ErrorInfo {
+        node_index: first.and_then(|p| p.node),
+        edge_index: first.and_then(|p| p.edge),
+        value: first.and_then(|p| p.value),
+        ..ErrorInfo::default()
+    };
+    let attach_problems = |instance: &PyAny| {
+        let list: Vec<ProblemTuple> = problems
+            .iter()
+            .map(|p| (p.edge, p.node, p.value, p.message.clone()))
+            .collect();
+        instance.setattr("problems", list)
+    };
+    if problems.iter().any(|p| p.kind == ProblemKind::EdgeType) {
+        new_error::<ChillEdgeTypeError>(message, info, attach_problems)
+    } else {
+        new_error::<ChillValidationError>(message, info, attach_problems)
+    }
+}
+
+/// `ChillValidationError` for a single invalid value passed to a setter.
+pub fn invalid_value(message: String, info: ErrorInfo) -> PyErr {
+    let problems: Vec<ProblemTuple> = vec![(info.edge_index, info.node_index, info.value, message.clone())];
+    new_error::<ChillValidationError>(message, info, |instance| instance.setattr("problems", problems))
+}
+
+pub fn instability_error(message: String, info: ErrorInfo) -> PyErr {
+    new_error::<ChillInstabilityError>(message, info, |_| Ok(()))
+}
+
+pub fn solver_error(message: String, info: ErrorInfo, iterations: usize, residual: f64) -> PyErr {
+    new_error::<ChillSolverError>(message, info, |instance| {
+        instance.setattr("iterations", iterations)?;
+        instance.setattr("residual", residual)
+    })
+}
+
+/// `ChillError` for a failed linear solve.
+pub fn linear_solver_error(error: SolveError, info: ErrorInfo) -> PyErr {
+    let (iterations, residual) = match error {
+        SolveError::NotConverged { iterations, residual } => (iterations, residual),
+        SolveError::Breakdown { iterations } => (iterations, f64::NAN),
+    };
+    solver_error(error.to_string(), info, iterations, residual)
+}
+
+/// Attach the last good temperatures of an aborted run as `temperatures`.
+pub fn with_temperatures(err: PyErr, temperatures: &[f64]) -> PyErr {
+    Python::with_gil(|py| {
+        match err
+            .value(py)
+            .setattr("temperatures", PyArray1::from_slice(py, temperatures))
+        {
+            Ok(()) => err,
+            Err(e) => e,
+        }
+    })
+}
+
+/// Failure of a single step of the stepping loop.
+#[derive(Debug)]
+pub enum StepError {
+    /// The temperatures after the step failed a check of the guard.
+    Guard(Violation),
+    /// dt exceeds the stable limit set by a node.
+    Unstable { node: usize, dt: f64, limit: f64 },
+    /// The linear solve of an implicit step failed.
+    Solver(SolveError),
+    /// A Python schedule function raised or returned a bad value.
+    Python(PyErr),
+}
+
+impl StepError {
+    /// Message, offending node and offending value.
+    pub fn describe(&self) -> (String, Option<usize>, Option<f64>) {
+        match *self {
+            StepError::Guard(violation) => {
+                let (node, value) = match violation {
+                    Violation::NonFinite { node, temperature } => (node, temperature),
+                    Violation::Delta { node, delta } => (node, delta),
+                    Violation::OutOfRange { node, temperature } => (node, temperature),
+                };
+                (violation.to_string(), Some(node), Some(value))
+            }
+            StepError::Unstable { node, dt, limit } => (
+                format!(
+                    "Time step dt = {} exceeds the stable limit {} set by node {}",
+                    dt, limit, node
+                ),
+                Some(node),
+                Some(dt),
+            ),
+            StepError::Solver(error) => (error.to_string(), None, None),
+            StepError::Python(ref error) => (error.to_string(), None, None),
+        }
+    }
+
+    /// Python exception for a failure at `step` (counted from the start of
+    /// the call), with the network stopped at simulated `time` and the last
+    /// good `temperatures`.
+    pub fn into_py_err(self, step: usize, time: f64, temperatures: &[f64]) -> PyErr {
+        let (message, node_index, value) = self.describe();
+        let info = ErrorInfo {
+            node_index,
+            value,
+            step: Some(step),
+            time: Some(time),
+            ..ErrorInfo::default()
+        };
+        let err = match self {
+            StepError::Python(error) => return error,
+            StepError::Solver(error) => linear_solver_error(error, info),
+            _ => instability_error(format!("{} (step {})", message, step), info),
+        };
+        with_temperatures(err, temperatures)
+    }
+}
+
+impl From<AdaptiveError> for PyErr {
+    fn from(error: AdaptiveError) -> PyErr {
+        match error {
+            AdaptiveError::StepSizeUnderflow { time, dt } => instability_error(
+                error.to_string(),
+                ErrorInfo {
+                    value: Some(dt),
+                    time: Some(time),
+                    ..ErrorInfo::default()
+                },
+            ),
+            AdaptiveError::TooManySteps { time, steps } => solver_error(
+                error.to_string(),
+                ErrorInfo {
+                    step: Some(steps),
+                    time: Some(time),
+                    ..ErrorInfo::default()
+                },
+                steps,
+                f64::NAN,
+            ),
+        }
+    }
+}
+
+impl From<SteadyStateError> for PyErr {
+    fn from(error: SteadyStateError) -> PyErr {
+        match error {
+            SteadyStateError::NoBoundary => {
+                let message = error.to_string();
+                let problems: Vec<ProblemTuple> = vec![(None, None, None, message.clone())];
+                new_error::<ChillValidationError>(message, ErrorInfo::default(), |instance| {
+                    instance.setattr("problems", problems)
+                })
+            }
+            SteadyStateError::Solver(e) => linear_solver_error(e, ErrorInfo::default()),
+            SteadyStateError::NotConverged {
+                iterations,
+                residual_norm,
+            } => solver_error(
+                error.to_string(),
+                ErrorInfo {
+                    value: Some(residual_norm),
+                    ..ErrorInfo::default()
+                },
+                iterations,
+                residual_norm,
+            ),
+        }
+    }
+}
+
+pub fn register(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add("ChillError", py.get_type::<ChillError>())?;
+    m.add("ChillValidationError", py.get_type::<ChillValidationError>())?;
+    m.add("ChillEdgeTypeError", py.get_type::<ChillEdgeTypeError>())?;
+    m.add("ChillInstabilityError", py.get_type::<ChillInstabilityError>())?;
+    m.add("ChillSolverError", py.get_type::<ChillSolverError>())?;
+    Ok(())
+}
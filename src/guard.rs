@@ -0,0 +1,131 @@
+use pyo3::prelude::*;
+
+use crate::errors::{invalid_value, ErrorInfo};
+
+/// Default largest temperature change of a single step that is not treated
+/// as a blow-up [K].
+pub const MAX_DELTA_TEMPERATURE: f64 = 1e8;
+
+/// Checks applied to the temperatures after every step.
+#[derive(Debug, Clone, Copy)]
+pub struct Guard {
+    /// Largest allowed |ΔT| of a single step [K].
+    pub max_delta: f64,
+    /// Allowed temperature range [K].
+    pub min_temperature: f64,
+    pub max_temperature: f64,
+    /// Reject NaN and infinite temperatures.
+    pub check_finite: bool,
+}
+
+impl Default for Guard {
+    fn default() -> Self {
+        Guard {
+            max_delta: MAX_DELTA_TEMPERATURE,
+            min_temperature: f64::NEG_INFINITY,
+            max_temperature: f64::INFINITY,
+            check_finite: true,
+        }
+    }
+}
+
+/// The first check a step failed.
+#[derive(Debug, Clone, Copy)]
+pub enum Violation {
+    NonFinite { node: usize, temperature: f64 },
+    Delta { node: usize, delta: f64 },
+    OutOfRange { node: usize, temperature: f64 },
+}
+
+impl Guard {
+    pub fn new(max_delta: f64, temperature_range: Option<(f64, f64)>, check_finite: bool) -> PyResult<Self> {
+        if max_delta.is_nan() || max_delta <= 0.0 {
+            return Err(invalid_value(
+                format!("max_delta must be positive, got {}", max_delta),
+                ErrorInfo {
+                    value: Some(max_delta),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        let (min_temperature, max_temperature) = temperature_range.unwrap_or((f64::NEG_INFINITY, f64::INFINITY));
+        if min_temperature.is_nan() || max_temperature.is_nan() || min_temperature >= max_temperature {
+            return Err(invalid_value(
+                format!("Invalid temperature range ({}, {})", min_temperature, max_temperature),
+                ErrorInfo::default(),
+            ));
+        }
+        Ok(Guard {
+            max_delta,
+            min_temperature,
+            max_temperature,
+            check_finite,
+        })
+    }
+
+    /// Check the step from `before` to `after`, node by node. Fixed nodes
+    /// take prescribed temperatures and are not checked.
+    pub fn check(&self, before: &[f64], after: &[f64], fixed: &[bool]) -> Result<(), Violation> {
+        for (node, (&t0, &t1)) in before.iter().zip(after).enumerate() {
+            if fixed[node] {
+                continue;
+            }
+            if !t1.is_finite() {
+                if self.check_finite {
+                    return Err(Violation::NonFinite { node, temperature: t1 });
+                }
+                continue;
+            }
+            let delta = t1 - t0;
+            if delta.abs() > self.max_delta {
+                return Err(Violation::Delta { node, delta });
+            }
+            if t1 < self.min_temperature || t1 > self.max_temperature {
+                return Err(Violation::OutOfRange { node, temperature: t1 });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::NonFinite { node, temperature } => {
+                write!(f, "Temperature of node {} became {}", node, temperature)
+            }
+            Violation::Delta { node, delta } => write!(
+                f,
+                "Unreasonably large temperature change at node {}: delta_temp = {}",
+                node, delta
+            ),
+            Violation::OutOfRange { node, temperature } => write!(
+                f,
+                "Temperature of node {} left the allowed range: {}",
+                node, temperature
+            ),
+        }
+    }
+}
+
+/// What the stepping loop does when a step fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureMode {
+    /// Raise an exception.
+    Raise,
+    /// Stop and keep the last good state.
+    Stop,
+}
+
+impl FailureMode {
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "raise" => Ok(FailureMode::Raise),
+            "stop" => Ok(FailureMode::Stop),
+            _ => Err(invalid_value(
+                format!("Unknown on_failure '{}', expected 'raise' or 'stop'", name),
+                ErrorInfo::default(),
+            )),
+        }
+    }
+}
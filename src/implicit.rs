@@ -0,0 +1,169 @@
+use crate::edge::Edge;
+use crate::sparse::{SolveError, SparseMatrix};
+
+/// Relative residual the linear solver has to reach in every step.
+const SOLVER_TOLERANCE: f64 = 1e-12;
+
+/// Linearly implicit stepper of the theta family.
+///
+/// Every step solves the sparse system
+///
+/// ```text
+/// (C / dt - theta * J) dT = F(T)
+/// ```
+///
+/// where `F` is the net heat flow into each node and `J = dF/dT` its Jacobian,
+/// i.e. radiation edges are linearized around the current temperatures.
+/// `theta = 1` is backward Euler. Fixed nodes keep their temperature.
+pub struct ThetaStepper {
+    theta: f64,
+    fixed: Vec<bool>,
+    matrix: SparseMatrix,
+    diag_slots: Vec<usize>,
+    /// Matrix positions of (n1, n1), (n1, n2), (n2, n1), (n2, n2) for each edge.
+    edge_slots: Vec<[Option<usize>; 4]>,
+    rhs: Vec<f64>,
+    delta: Vec<f64>,
+}
+
+impl ThetaStepper {
+    pub fn new(fixed: &[bool], edges: &[Edge], theta: f64) -> Self {
+        let n = fixed.len();
+        let fixed = fixed.to_vec();
+
+        let mut pattern: Vec<(usize, usize)> = (0..n).map(|i| (i, i)).collect();
+        for edge in edges {
+            for (row, col) in Self::edge_entries(edge).into_iter().flatten() {
+                if !fixed[row] && !fixed[col] {
+                    pattern.push((row, col));
+                }
+            }
+        }
+        let matrix = SparseMatrix::from_pattern(n, &pattern);
+
+        let diag_slots = (0..n).map(|i| matrix.position(i, i).unwrap()).collect();
+        let edge_slots = edges
+            .iter()
+            .map(|edge| {
+                let mut slots = [None; 4];
+                for (slot, entry) in slots.iter_mut().zip(Self::edge_entries(edge)) {
+                    if let Some((row, col)) = entry {
+                        if !fixed[row] && !fixed[col] {
+                            *slot = matrix.position(row, col);
+                        }
+                    }
+                }
+                slots
+            })
+            .collect();
+
+        ThetaStepper {
+            theta,
+            fixed,
+            matrix,
+            diag_slots,
+            edge_slots,
+            rhs: vec![0.0; n],
+            delta: vec![0.0; n],
+        }
+    }
+
+    pub fn theta(&self) -> f64 {
+        self.theta
+    }
+
+    /// (row, col) entries of the Jacobian touched by an edge, in slot order.
+    /// The rows of `n1` are absent if the edge only feeds `n2`.
+    fn edge_entries(edge: &Edge) -> [Option<(usize, usize)>; 4] {
+        let (n1, n2) = (edge.n1, edge.n2);
+        let exchange = edge.edge_type.is_exchange();
+        [
+            Some((n1, n1)).filter(|_| exchange),
+            Some((n1, n2)).filter(|_| exchange),
+            Some((n2, n1)),
+            Some((n2, n2)),
+        ]
+    }
+
+    /// Advance `temperatures` by one step of size `dt`.
+    ///
+    /// Returns the number of linear solver iterations used.
+    pub fn step(
+        &mut self,
+        edges: &[Edge],
+        capacities: &[f64],
+        temperatures: &mut [f64],
+        dt: f64,
+    ) -> Result<usize, SolveError> {
+        self.assemble(edges, capacities, temperatures, dt);
+        let iterations = self.solve()?;
+        for (t, d) in temperatures.iter_mut().zip(&self.delta) {
+            *t += d;
+        }
+        Ok(iterations)
+    }
+
+    /// Solve the assembled system for the temperature change.
+    ///
+    /// Returns the number of linear solver iterations used.
+    pub fn solve(&mut self) -> Result<usize, SolveError> {
+        let max_iter = 10 * self.delta.len() + 100;
+        self.matrix
+            .solve(&self.rhs, &mut self.delta, SOLVER_TOLERANCE, max_iter)
+    }
+
+    /// Heat flow through each edge applied by the last step from `start`
+    /// [W]: the flow linearized to the temperatures T + theta * dT.
+    pub fn edge_flows(&self, edges: &[Edge], start: &[f64], flows: &mut [f64]) {
+        for (flow, edge) in flows.iter_mut().zip(edges) {
+            let (dq1, dq2) = edge.heat_flow_derivatives(start);
+            let change = dq1 * self.delta[edge.n1] + dq2 * self.delta[edge.n2];
+            *flow = edge.heat_flow(start) + self.theta * change;
+        }
+    }
+
+    /// Temperature change found by the last `solve`.
+    pub fn delta(&self) -> &[f64] {
+        &self.delta
+    }
+
+    /// Euclidean norm of the net heat flow into the free nodes [W] at the
+    /// temperatures of the last `assemble`.
+    pub fn residual_norm(&self) -> f64 {
+        self.rhs.iter().map(|r| r * r).sum::<f64>().sqrt()
+    }
+
+    /// Assemble the system around `temperatures`.
+    ///
+    /// With `dt = inf` this is the Newton system of the steady state.
+    pub fn assemble(&mut self, edges: &[Edge], capacities: &[f64], temperatures: &[f64], dt: f64) {
+        self.matrix.clear();
+        self.rhs.iter_mut().for_each(|r| *r = 0.0);
+
+        for (i, &capacity) in capacities.iter().enumerate() {
+            self.matrix.values[self.diag_slots[i]] = if self.fixed[i] { 1.0 } else { capacity / dt };
+        }
+
+        for (edge, slots) in edges.iter().zip(&self.edge_slots) {
+            let q = edge.heat_flow(temperatures);
+            let (dq1, dq2) = edge.heat_flow_derivatives(temperatures);
+            self.rhs[edge.n2] += q;
+            if edge.edge_type.is_exchange() {
+                self.rhs[edge.n1] -= q;
+            }
+            // -dF/dT: n1 loses q, n2 gains it.
+            let minus_jacobian = [dq1, dq2, -dq1, -dq2];
+            for (slot, value) in slots.iter().zip(minus_jacobian) {
+                if let Some(slot) = slot {
+                    self.matrix.values[*slot] += self.theta * value;
+                }
+            }
+        }
+
+        for (rhs, &fixed) in self.rhs.iter_mut().zip(&self.fixed) {
+            if fixed {
+                *rhs = 0.0;
+            }
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use pyo3::prelude::*;
+
+use crate::edge::{edge_flows, heat_balance, Edge};
+use crate::errors::{invalid_value, ErrorInfo, StepError};
+
+/// Time integration scheme selected by the `method` argument of `process`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Euler,
+    Rk4,
+    BackwardEuler,
+    CrankNicolson,
+}
+
+impl Method {
+    pub fn name(self) -> &'static str {
+        match self {
+            Method::Euler => "euler",
+            Method::Rk4 => "rk4",
+            Method::BackwardEuler => "implicit",
+            Method::CrankNicolson => "crank_nicolson",
+        }
+    }
+
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "euler" => Ok(Method::Euler),
+            "rk4" => Ok(Method::Rk4),
+            "implicit" | "backward_euler" => Ok(Method::BackwardEuler),
+            "crank_nicolson" | "cn" => Ok(Method::CrankNicolson),
+            _ => Err(invalid_value(
+                format!(
+                    "Unknown method: '{}' (expected 'euler', 'rk4', 'implicit' or 'crank_nicolson')",
+                    name
+                ),
+                ErrorInfo::default(),
+            )),
+        }
+    }
+
+    /// Implicitness of the theta scheme, `None` for explicit methods.
+    ///
+    /// `damping` in [0, 0.5] off-centers Crank–Nicolson towards backward
+    /// Euler (theta = 0.5 + damping) to suppress oscillations at very large
+    /// steps.
+    pub fn theta(self, damping: f64) -> Option<f64> {
+        match self {
+            Method::Euler | Method::Rk4 => None,
+            Method::BackwardEuler => Some(1.0),
+            Method::CrankNicolson => Some(0.5 + damping),
+        }
+    }
+}
+
+/// How `process` treats the stable time step of the explicit schemes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepLimit {
+    /// Step with the given dt regardless of the limit.
+    Ignore,
+    /// Raise if dt exceeds the limit.
+    Enforce,
+    /// Split each step into as many equal sub-steps as needed to respect the limit.
+    Substep,
+}
+
+impl StepLimit {
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "none" => Ok(StepLimit::Ignore),
+            "enforce" => Ok(StepLimit::Enforce),
+            "substep" => Ok(StepLimit::Substep),
+            _ => Err(invalid_value(
+                format!("Unknown dt_limit: '{}' (expected 'none', 'enforce' or 'substep')", name),
+                ErrorInfo::default(),
+            )),
+        }
+    }
+
+    /// Number and size of the sub-steps to take for a step of `dt`.
+    pub fn substeps(
+        self,
+        edges: &[Edge],
+        capacities: &[f64],
+        fixed: &[bool],
+        temperatures: &[f64],
+        dt: f64,
+    ) -> Result<(usize, f64), StepError> {
+        if self == StepLimit::Ignore {
+            return Ok((1, dt));
+        }
+        let (limit, node) = stable_time_step(edges, capacities, fixed, temperatures);
+        let node = match node {
+            Some(node) if dt > limit => node,
+            _ => return Ok((1, dt)),
+        };
+        if self == StepLimit::Enforce || limit == 0.0 {
+            return Err(StepError::Unstable { node, dt, limit });
+        }
+        let substeps = (dt / limit).ceil() as usize;
+        Ok((substeps, dt / substeps as f64))
+    }
+}
+
+/// Largest time step for which explicit Euler stays stable, and the node
+/// that sets it.
+///
+/// Each free node i limits dt to C_i / sum_j G_ij, where G is the
+/// conductance of a Transfer edge or the conductance 4 * parameter * T^3 of a
+/// Radiation edge linearized at the current temperature. Fixed nodes are
+/// ignored. Returns `(inf, None)` if nothing limits the step.
+pub fn stable_time_step(edges: &[Edge], capacities: &[f64], fixed: &[bool], temperatures: &[f64]) -> (f64, Option<usize>) {
+    let mut conductances = vec![0.0; capacities.len()];
+    for edge in edges {
+        // diagonal of -dF/dT
+        let (dq1, dq2) = edge.heat_flow_derivatives(temperatures);
+        if edge.edge_type.is_exchange() {
+            conductances[edge.n1] += dq1;
+        }
+        conductances[edge.n2] -= dq2;
+    }
+
+    let mut limit = (f64::INFINITY, None);
+    for (i, (&capacity, &conductance)) in capacities.iter().zip(&conductances).enumerate() {
+        if fixed[i] || conductance <= 0.0 {
+            continue;
+        }
+        let dt = capacity / conductance;
+        if dt < limit.0 {
+            limit = (dt, Some(i));
+        }
+    }
+    limit
+}
+
+/// Nodes held out of the integration when nothing but the capacities
+/// tells: those with infinite capacity.
+pub fn fixed_nodes(capacities: &[f64]) -> Vec<bool> {
+    capacities.iter().map(|c| c.is_infinite()).collect()
+}
+
+/// Time derivative of the temperatures, dT/dt = F(T) / C.
+///
+/// Fixed nodes get a zero rate whatever flows into them.
+pub fn temperature_rates(edges: &[Edge], capacities: &[f64], fixed: &[bool], temperatures: &[f64], rates: &mut [f64]) {
+    heat_balance(edges, temperatures, rates);
+    for ((rate, capacity), &fixed) in rates.iter_mut().zip(capacities).zip(fixed) {
+        *rate = if fixed { 0.0 } else { *rate / capacity };
+    }
+}
+
+/// Advance `temperatures` by one explicit Euler step of size `dt`.
+///
+/// `rates` is scratch space of the same length as `temperatures`.
+pub fn euler_step(
+    edges: &[Edge],
+    capacities: &[f64],
+    fixed: &[bool],
+    temperatures: &mut [f64],
+    dt: f64,
+    rates: &mut [f64],
+) {
+    temperature_rates(edges, capacities, fixed, temperatures, rates);
+    for (t, rate) in temperatures.iter_mut().zip(rates.iter()) {
+        *t += rate * dt;
+    }
+}
+
+/// Classic fourth-order Runge–Kutta stepper with reusable stage buffers.
+pub struct Rk4 {
+    k1: Vec<f64>,
+    k2: Vec<f64>,
+    k3: Vec<f64>,
+    k4: Vec<f64>,
+    stage: Vec<f64>,
+    /// Heat flow through each edge at a stage [W], scratch space.
+    stage_flows: Vec<f64>,
+}
+
+impl Rk4 {
+    pub fn new(n: usize) -> Self {
+        Rk4 {
+            k1: vec![0.0; n],
+            k2: vec![0.0; n],
+            k3: vec![0.0; n],
+            k4: vec![0.0; n],
+            stage: vec![0.0; n],
+            stage_flows: Vec::new(),
+        }
+    }
+
+    /// Advance `temperatures` by one step of size `dt`.
+    pub fn step(&mut self, edges: &[Edge], capacities: &[f64], fixed: &[bool], temperatures: &mut [f64], dt: f64) {
+        temperature_rates(edges, capacities, fixed, temperatures, &mut self.k1);
+        for ((s, t), k) in self.stage.iter_mut().zip(temperatures.iter()).zip(&self.k1) {
+            *s = t + 0.5 * dt * k;
+        }
+        temperature_rates(edges, capacities, fixed, &self.stage, &mut self.k2);
+        for ((s, t), k) in self.stage.iter_mut().zip(temperatures.iter()).zip(&self.k2) {
+            *s = t + 0.5 * dt * k;
+        }
+        temperature_rates(edges, capacities, fixed, &self.stage, &mut self.k3);
+        for ((s, t), k) in self.stage.iter_mut().zip(temperatures.iter()).zip(&self.k3) {
+            *s = t + dt * k;
+        }
+        temperature_rates(edges, capacities, fixed, &self.stage, &mut self.k4);
+
+        for (idx, t) in temperatures.iter_mut().enumerate() {
+            *t += dt / 6.0 * (self.k1[idx] + 2.0 * self.k2[idx] + 2.0 * self.k3[idx] + self.k4[idx]);
+        }
+    }
+
+    /// Heat flow through each edge applied by the last step from `start` [W]:
+    /// the stage flows weighted like the stage rates.
+    pub fn edge_flows(&mut self, edges: &[Edge], start: &[f64], dt: f64, flows: &mut [f64]) {
+        self.stage_flows.resize(edges.len(), 0.0);
+        edge_flows(edges, start, flows);
+        for (k, fraction, weight) in [(&self.k1, 0.5, 2.0), (&self.k2, 0.5, 2.0), (&self.k3, 1.0, 1.0)] {
+            for ((s, t), k) in self.stage.iter_mut().zip(start).zip(k) {
+                *s = t + fraction * dt * k;
+            }
+            edge_flows(edges, &self.stage, &mut self.stage_flows);
+            for (flow, stage_flow) in flows.iter_mut().zip(&self.stage_flows) {
+                *flow += weight * stage_flow;
+            }
+        }
+        for flow in flows.iter_mut() {
+            *flow /= 6.0;
+        }
+    }
+}
@@ -1,76 +1,366 @@
+use std::collections::HashMap;
+
 use numpy::{PyArray1, PyReadonlyArray1, PyReadonlyArray2};
 use pyo3::prelude::*;
-use pyo3::{exceptions::PyRuntimeError, wrap_pyfunction};
+use pyo3::types::PyDict;
+use pyo3::wrap_pyfunction;
 
-#[derive(Debug, Clone, Copy)]
-enum EdgeType {
-    Transfer,
-    Radiation,
-    HeatInput,
-}
+mod adaptive;
+mod control;
+mod convection;
+mod edge;
+mod energy;
+mod errors;
+mod guard;
+mod implicit;
+mod integrator;
+mod network;
+mod profile;
+mod property;
+mod sparse;
+mod steady;
+mod validation;
+
+use adaptive::integrate_dopri5;
+use control::{Controller, PidController, Thermostat};
+use convection::{Convection, Fluid};
+use errors::with_temperatures;
+use guard::{FailureMode, Guard, MAX_DELTA_TEMPERATURE};
+use integrator::{fixed_nodes, stable_time_step as euler_stable_time_step, Method, StepLimit};
+use network::Network;
+use profile::{Profile, Schedule};
+use property::{Conductivity, HeatCapacity, PhaseChange};
+use validation::validate_network;
+use steady::solve_steady_state as newton_steady_state;
 
-impl EdgeType {
-    fn from_i32(value: i32) -> Option<Self> {
-        match value {
-            0 => Some(EdgeType::Transfer),
-            1 => Some(EdgeType::Radiation),
-            2 => Some(EdgeType::HeatInput),
-            _ => None,
-        }
+#[pyfunction(
+    method = "\"euler\"",
+    damping = "0.0",
+    dt_limit = "\"none\"",
+    edge_flows = "false",
+    energy_balance = "false",
+    max_delta = "MAX_DELTA_TEMPERATURE",
+    temperature_range = "None",
+    check_finite = "true",
+    on_failure = "\"raise\"",
+    min_dt = "None",
+    boundaries = "None",
+    heat_inputs = "None",
+    controllers = "None",
+    conductivities = "None",
+    heat_capacities = "None",
+    phase_changes = "None",
+    convections = "None",
+    mass_flows = "None"
+)]
+/// Process thermal changes over a certain number of steps.
+///
+/// Parameters
+/// ----------
+/// temperatures : ndarray of shape (N, )
+///     Initial temperatures of each node.
+/// capacities : ndarray of shape (N, )
+///     Heat capacities for each node.
+/// parameters : ndarray of shape (E, )
+///     Parameters for each edge (depending on the edge type).
+/// connections : ndarray of shape (E, 2)
+///     Each row represents an edge, giving the two connected node indices.
+/// edge_types : ndarray of shape (E, )
+///     Integer codes defining the type of each edge (0: Transfer, 1: Radiation, 2: HeatInput, 3: Convection, 4: Advection).
+/// dt : float
+///     Time step for the simulation, finite and positive.
+/// steps : int
+///     Number of steps to simulate.
+/// method : str, optional
+///     Time integration scheme: 'euler' (explicit, default), 'rk4' (explicit,
+///     fourth order), 'implicit' (backward Euler) or 'crank_nicolson' (second order).
+/// damping : float, optional
+///     Off-centering of Crank–Nicolson towards backward Euler in [0, 0.5]
+///     (theta = 0.5 + damping). Damps oscillations at very large steps.
+///     Default is 0 (pure Crank–Nicolson).
+/// dt_limit : str, optional
+///     Handling of the stable time step of the explicit methods (see
+///     `stable_time_step`), re-evaluated every step: 'none' (default),
+///     'enforce' (raise if dt exceeds it) or 'substep' (split steps to respect it).
+/// edge_flows : bool, optional
+///     Also return the heat flow through each edge averaged over the run as
+///     'edge_flows'. Default is False.
+/// energy_balance : bool, optional
+///     Also return the energy bookkeeping of the run as 'energy', see
+///     `Network.energy_balance`. Default is False.
+/// max_delta : float, optional
+///     Largest temperature change of a node in a single step [K]. Default is 1e8.
+/// temperature_range : (float, float), optional
+///     Allowed (min, max) temperature [K]. Default is None (unbounded).
+/// check_finite : bool, optional
+///     Treat NaN and infinite temperatures as a failure. Default is True.
+/// on_failure : str, optional
+///     What to do when a step fails: 'raise' (default) a `ChillError` that
+///     carries the last good `temperatures`, the failing `step` and the
+///     simulated `time`, or 'stop' and return the state before the failing
+///     step with the failure as 'failure'.
+/// min_dt : float, optional
+///     Enables step halving: a failing step is rolled back and redone as two
+///     steps of half the size, recursively, down to `min_dt`. Only a failure
+///     at `min_dt` is handled by `on_failure`. Default is None (disabled).
+/// boundaries : dict, optional
+///     Boundary nodes whose temperature is prescribed rather than integrated,
+///     mapping the node index to a constant temperature, a (times,
+///     temperatures) table, a `Profile` or a function of the simulated time,
+///     see `Network.set_boundary`. Default is None.
+/// heat_inputs : dict, optional
+///     Power profiles of HeatInput edges, mapping the edge index to a
+///     (times, powers) table, a `Profile` or a function of the simulated
+///     time, evaluated at the time of each step. See `Network.set_heat_input`.
+///     Default is None.
+/// controllers : list, optional
+///     Controllers (`Thermostat`, `PidController`) driving HeatInput edges,
+///     evaluated at every step. See `Network.add_controller`. Default is None.
+/// conductivities : dict, optional
+///     Temperature-dependent Transfer edges, mapping the edge index to a
+///     (conductivity, geometry) or (conductivity, geometry, mode) tuple, see
+///     `Network.set_conductivity`. Default is None.
+/// heat_capacities : dict, optional
+///     Temperature-dependent heat capacities, mapping the node index to a
+///     (temperatures, capacities) table, a `Profile` over temperature or a
+///     function of temperature. These nodes are stepped in enthalpy, see
+///     `Network.set_heat_capacity`. Default is None.
+/// phase_changes : dict, optional
+///     Nodes that melt and freeze, mapping the node index to a
+///     (melt_temperature, latent_heat, width) tuple, see
+///     `Network.set_phase_change`. Default is None.
+/// convections : dict, optional
+///     Heat transfer coefficients of Convection edges, mapping the edge index
+///     to a `Convection` power law or correlation, see
+///     `Network.set_convection`. Default is None.
+/// mass_flows : dict, optional
+///     Mass flows of Advection edges, mapping the edge index to a
+///     (mass_flow, specific_heat) tuple where the mass flow [kg/s] is a
+///     constant, a (times, flows) table, a `Profile` or a function of the
+///     simulated time, see `Network.set_mass_flow`. Default is None.
+///
+/// Returns
+/// -------
+/// ndarray of shape (N, )
+///     The updated temperatures after simulation.
+/// dict
+///     Only returned, as the second element of a tuple, if one of the
+///     options below asks for it. Its keys are:
+///
+///     - 'edge_flows' (if `edge_flows` is True): ndarray of shape (E, ), the
+///       heat flow through each edge from its first to its second node,
+///       averaged over the run [W].
+///     - 'energy' (if `energy_balance` is True): energy injected by HeatInput
+///       edges ('heat_input'), received from the boundary nodes and the nodes
+///       with infinite capacity ('boundary'), the change of the stored energy
+///       ('stored') and the balance error ('error') [J].
+///     - 'failure' (if `on_failure` is 'stop'): the failure that stopped the
+///       run ('step', 'time', 'node_index', 'value', 'message'), None if it
+///       ran through.
+///     - 'halvings' (if `min_dt` is given): number of times a step was halved.
+///     - 'controllers' (if `controllers` is given): state of each controller
+///       at the end of the run, see `Network.controllers`.
+///     - 'melt_fractions' (if `phase_changes` is given): ndarray of shape
+///       (N, ), the liquid fraction of each node at the end of the run, NaN
+///       for the nodes without a phase change.
+#[allow(clippy::too_many_arguments)]
+fn process(
+    py: Python,
+    temperatures: PyReadonlyArray1<f64>,
+    capacities: PyReadonlyArray1<f64>,
+    parameters: PyReadonlyArray1<f64>,
+    connections: PyReadonlyArray2<usize>,
+    edge_types: PyReadonlyArray1<i32>,
+    dt: f64,
+    steps: usize,
+    method: &str,
+    damping: f64,
+    dt_limit: &str,
+    edge_flows: bool,
+    energy_balance: bool,
+    max_delta: f64,
+    temperature_range: Option<(f64, f64)>,
+    check_finite: bool,
+    on_failure: &str,
+    min_dt: Option<f64>,
+    boundaries: Option<HashMap<usize, &PyAny>>,
+    heat_inputs: Option<HashMap<usize, &PyAny>>,
+    controllers: Option<Vec<&PyAny>>,
+    conductivities: Option<HashMap<usize, &PyAny>>,
+    heat_capacities: Option<HashMap<usize, &PyAny>>,
+    phase_changes: Option<HashMap<usize, (f64, f64, f64)>>,
+    convections: Option<HashMap<usize, Convection>>,
+    mass_flows: Option<HashMap<usize, (&PyAny, f64)>>,
+) -> PyResult<PyObject> {
+    let edges = validate_network(
+        temperatures.as_array(),
+        capacities.as_array(),
+        parameters.as_array(),
+        connections.as_array(),
+        edge_types.as_array(),
+    )?;
+    let mut network = Network::build(
+        temperatures.as_array().to_vec(),
+        capacities.as_array().to_vec(),
+        edges,
+        dt,
+        Method::from_name(method)?,
+        damping,
+        StepLimit::from_name(dt_limit)?,
+    )?;
+    if edge_flows {
+        network.track_edge_flows();
+    }
+    if energy_balance {
+        network.track_energy();
+    }
+    let on_failure = FailureMode::from_name(on_failure)?;
+    network.set_guard(Guard::new(max_delta, temperature_range, check_finite)?, on_failure);
+    network.set_step_halving(min_dt)?;
+    for (node, temperature) in boundaries.unwrap_or_default() {
+        network.set_boundary(node, Schedule::extract(temperature)?)?;
+    }
+    for (edge, power) in heat_inputs.unwrap_or_default() {
+        network.set_heat_input(edge, Schedule::extract(power)?)?;
+    }
+    for (node, capacity) in heat_capacities.unwrap_or_default() {
+        network.set_heat_capacity(node, HeatCapacity::new(Schedule::extract(capacity)?)?)?;
+    }
+    let with_phase_changes = phase_changes.is_some();
+    for (node, (melt_temperature, latent_heat, width)) in phase_changes.unwrap_or_default() {
+        network.set_phase_change(node, PhaseChange::new(melt_temperature, latent_heat, width)?)?;
+    }
+    for (edge, convection) in convections.unwrap_or_default() {
+        network.set_convection(edge, convection)?;
+    }
+    for (edge, (mass_flow, specific_heat)) in mass_flows.unwrap_or_default() {
+        network.set_mass_flow(edge, Schedule::extract(mass_flow)?, specific_heat)?;
     }
+    for (edge, conductivity) in conductivities.unwrap_or_default() {
+        network.set_conductivity(edge, Conductivity::extract(conductivity)?)?;
+    }
+    for controller in controllers.iter().flatten() {
+        network.add_controller(Controller::extract(controller)?)?;
+    }
+    network.advance(steps)?;
+
+    let results = PyDict::new(py);
+    if let Some(flows) = network.mean_edge_flows() {
+        results.set_item("edge_flows", PyArray1::from_vec(py, flows))?;
+    }
+    if let Some(report) = network.energy_report(py)? {
+        results.set_item("energy", report)?;
+    }
+    if on_failure == FailureMode::Stop {
+        let failure = network.failure().map(|failure| failure.to_dict(py)).transpose()?;
+        results.set_item("failure", failure)?;
+    }
+    if min_dt.is_some() {
+        results.set_item("halvings", network.halvings())?;
+    }
+    if controllers.is_some() {
+        results.set_item("controllers", network.controller_reports(py)?)?;
+    }
+    if with_phase_changes {
+        results.set_item("melt_fractions", PyArray1::from_vec(py, network.melt_fractions()))?;
+    }
+    let array = PyArray1::from_vec(py, network.into_temperatures());
+    if results.is_empty() {
+        return Ok(array.into_py(py));
+    }
+    Ok((array, results).into_py(py))
 }
 
-/// Update the temperature changes for a single edge.
-///
-/// # Arguments
-///
-/// * `edge_type` - The type of the edge (Transfer, Radiation, HeatInput).
-/// * `parameter` - The parameter associated with the edge type.
-/// * `dt` - Time step.
-/// * `capacities` - Slice of capacities for each node.
-/// * `temperatures` - Slice of current temperatures for each node.
-/// * `n1` - The index of the first node connected by this edge.
-/// * `n2` - The index of the second node connected by this edge.
-/// * `updates` - A vector to accumulate (node_index, delta_temperature) pairs.
-fn update_edge(
-    edge_type: EdgeType,
-    parameter: f64,
+#[pyfunction(
+    record_every = "1",
+    method = "\"euler\"",
+    damping = "0.0",
+    dt_limit = "\"none\"",
+    edge_flows = "false"
+)]
+/// Process thermal changes over a certain number of steps, recording the
+/// temperatures every `record_every` steps.
+///
+/// Parameters
+/// ----------
+/// temperatures : ndarray of shape (N, )
+///     Initial temperatures of each node.
+/// capacities : ndarray of shape (N, )
+///     Heat capacities for each node.
+/// parameters : ndarray of shape (E, )
+///     Parameters for each edge (depending on the edge type).
+/// connections : ndarray of shape (E, 2)
+///     Each row represents an edge, giving the two connected node indices.
+/// edge_types : ndarray of shape (E, )
+///     Integer codes defining the type of each edge (0: Transfer, 1: Radiation, 2: HeatInput, 3: Convection, 4: Advection).
+/// dt : float
+///     Time step for the simulation, finite and positive.
+/// steps : int
+///     Number of steps to simulate.
+/// record_every : int, optional
+///     Number of steps between records. Default is 1.
+/// method : str, optional
+///     Time integration scheme, see `process`. Default is 'euler'.
+/// damping : float, optional
+///     Off-centering of Crank–Nicolson, see `process`. Default is 0.
+/// dt_limit : str, optional
+///     Handling of the stable time step, see `process`. Default is 'none'.
+/// edge_flows : bool, optional
+///     Also record the heat flow through each edge. Default is False.
+///
+/// Returns
+/// -------
+/// times : ndarray of shape (n_records, )
+///     Simulated time of each record, with n_records = steps // record_every.
+/// temperatures : ndarray of shape (n_records, N)
+///     Temperatures of each node at each record.
+/// edge_flows : ndarray of shape (n_records, E)
+///     Heat flow through each edge at each record [W]. Only returned if
+///     `edge_flows` is True.
+#[allow(clippy::too_many_arguments)]
+fn process_history(
+    py: Python,
+    temperatures: PyReadonlyArray1<f64>,
+    capacities: PyReadonlyArray1<f64>,
+    parameters: PyReadonlyArray1<f64>,
+    connections: PyReadonlyArray2<usize>,
+    edge_types: PyReadonlyArray1<i32>,
     dt: f64,
-    capacities: &[f64],
-    temperatures: &[f64],
-    n1: usize,
-    n2: usize,
-    updates: &mut Vec<(usize, f64)>,
-) -> PyResult<()> {
-    let delta_temp = match edge_type {
-        EdgeType::Transfer => {
-            // parameter is heat resistance
-            (temperatures[n2] - temperatures[n1]) / parameter * dt
-        }
-        EdgeType::Radiation => {
-            // parameter is ε * E_G * σ * A
-            (temperatures[n2].powi(4) - temperatures[n1].powi(4)) * parameter * dt
-        }
-        EdgeType::HeatInput => {
-            // parameter is Q [W] (heat input to n2)
-            parameter * dt
-        }
-    };
-
-    match edge_type {
-        EdgeType::Transfer | EdgeType::Radiation => {
-            updates.push((n1, delta_temp / capacities[n1]));
-            updates.push((n2, -delta_temp / capacities[n2]));
-        }
-        EdgeType::HeatInput => {
-            updates.push((n2, delta_temp / capacities[n2]));
-        }
-    }
-    Ok(())
+    steps: usize,
+    record_every: usize,
+    method: &str,
+    damping: f64,
+    dt_limit: &str,
+    edge_flows: bool,
+) -> PyResult<PyObject> {
+    let edges = validate_network(
+        temperatures.as_array(),
+        capacities.as_array(),
+        parameters.as_array(),
+        connections.as_array(),
+        edge_types.as_array(),
+    )?;
+    let mut network = Network::build(
+        temperatures.as_array().to_vec(),
+        capacities.as_array().to_vec(),
+        edges,
+        dt,
+        Method::from_name(method)?,
+        damping,
+        StepLimit::from_name(dt_limit)?,
+    )?;
+    let recording = network.advance_recording(steps, record_every, edge_flows)?;
+
+    Ok(recording.into_py(py))
 }
 
 #[pyfunction]
-/// Process thermal changes over a certain number of steps.
+/// Process thermal changes over a certain number of steps with the implicit
+/// (backward Euler) scheme.
+///
+/// Every step assembles and solves the sparse conductance system, so the
+/// integration stays stable for any time step. Radiation edges are linearized
+/// around the current temperatures. Nodes with infinite capacity are held fixed.
 ///
 /// Parameters
 /// ----------
@@ -83,9 +373,9 @@ fn update_edge(
 /// connections : ndarray of shape (E, 2)
 ///     Each row represents an edge, giving the two connected node indices.
 /// edge_types : ndarray of shape (E, )
-///     Integer codes defining the type of each edge (0: Transfer, 1: Radiation, 2: HeatInput).
+///     Integer codes defining the type of each edge (0: Transfer, 1: Radiation, 2: HeatInput, 3: Convection, 4: Advection).
 /// dt : float
-///     Time step for the simulation.
+///     Time step for the simulation, finite and positive.
 /// steps : int
 ///     Number of steps to simulate.
 ///
@@ -93,7 +383,8 @@ fn update_edge(
 /// -------
 /// ndarray of shape (N, )
 ///     The updated temperatures after simulation.
-fn process(
+#[allow(clippy::too_many_arguments)]
+fn process_implicit(
     py: Python,
     temperatures: PyReadonlyArray1<f64>,
     capacities: PyReadonlyArray1<f64>,
@@ -101,62 +392,232 @@ fn process(
     connections: PyReadonlyArray2<usize>,
     edge_types: PyReadonlyArray1<i32>,
     dt: f64,
-    steps: i32,
+    steps: usize,
 ) -> PyResult<Py<PyArray1<f64>>> {
+    let edges = validate_network(
+        temperatures.as_array(),
+        capacities.as_array(),
+        parameters.as_array(),
+        connections.as_array(),
+        edge_types.as_array(),
+    )?;
+    let mut network = Network::build(
+        temperatures.as_array().to_vec(),
+        capacities.as_array().to_vec(),
+        edges,
+        dt,
+        Method::BackwardEuler,
+        0.0,
+        StepLimit::Ignore,
+    )?;
+    network.advance(steps)?;
 
-    let mut temperatures = temperatures.as_array().to_owned(); // Array1<f64>
-    let capacities = capacities.as_array();
-    let parameters = parameters.as_array();
-    let connections = connections.as_array();
-    let edge_types = edge_types.as_array();
-
-    let max_calc_size = connections.len();
-
-    for _i in 0..steps {
-        let mut update_list: Vec<(usize, f64)> = Vec::with_capacity(max_calc_size * 2);
-
-        for (((&edge_type_int, &parameter), conn), _i)
-            in edge_types.iter()
-                .zip(parameters.iter())
-                .zip(connections.outer_iter())
-                .zip(0..) 
-        {
-            let edge_type = EdgeType::from_i32(edge_type_int)
-                .ok_or_else(|| PyRuntimeError::new_err(format!("Undefined edge type: {}", edge_type_int)))?;
-            let n1 = conn[0];
-            let n2 = conn[1];
-
-            update_edge(
-                edge_type,
-                parameter,
-                dt,
-                capacities.as_slice().unwrap(),
-                temperatures.as_slice().unwrap(),
-                n1,
-                n2,
-                &mut update_list
-            )?;
-        }
-
-        // Apply updates
-        for (idx, delta_temp) in update_list {
-            if delta_temp.abs() > 1e8 {
-                return Err(PyRuntimeError::new_err(format!(
-                    "Unreasonably large temperature change at node {}: delta_temp = {}",
-                    idx, delta_temp
-                )));
-            }
-            temperatures[idx] += delta_temp;
-        }
-    }
-
-    let array = PyArray1::from_vec(py, temperatures.to_vec());
+    let array = PyArray1::from_vec(py, network.into_temperatures());
     Ok(array.to_owned())
 }
 
+#[pyfunction(atol = "1e-6", rtol = "1e-6", first_step = "None", max_steps = "1_000_000")]
+/// Process thermal changes up to a given time with adaptive time stepping.
+///
+/// Uses the Dormand–Prince 5(4) embedded Runge–Kutta pair and adjusts the
+/// step size so that the local error of every node stays within
+/// atol + rtol * |T|.
+///
+/// Parameters
+/// ----------
+/// temperatures : ndarray of shape (N, )
+///     Initial temperatures of each node.
+/// capacities : ndarray of shape (N, )
+///     Heat capacities for each node.
+/// parameters : ndarray of shape (E, )
+///     Parameters for each edge (depending on the edge type).
+/// connections : ndarray of shape (E, 2)
+///     Each row represents an edge, giving the two connected node indices.
+/// edge_types : ndarray of shape (E, )
+///     Integer codes defining the type of each edge (0: Transfer, 1: Radiation, 2: HeatInput, 3: Convection, 4: Advection).
+/// t_end : float
+///     Simulated time to integrate over.
+/// atol : float, optional
+///     Absolute tolerance [K]. Default is 1e-6.
+/// rtol : float, optional
+///     Relative tolerance. Default is 1e-6.
+/// first_step : float, optional
+///     Initial step size. Estimated from the initial rates if omitted.
+/// max_steps : int, optional
+///     Maximum number of attempted steps. Default is 1,000,000.
+///
+/// Returns
+/// -------
+/// ndarray of shape (N, )
+///     The updated temperatures after simulation.
+/// dict
+///     Statistics with keys 'accepted_steps', 'rejected_steps', 'min_dt' and 'max_dt'.
+#[allow(clippy::too_many_arguments)]
+fn process_adaptive(
+    py: Python,
+    temperatures: PyReadonlyArray1<f64>,
+    capacities: PyReadonlyArray1<f64>,
+    parameters: PyReadonlyArray1<f64>,
+    connections: PyReadonlyArray2<usize>,
+    edge_types: PyReadonlyArray1<i32>,
+    t_end: f64,
+    atol: f64,
+    rtol: f64,
+    first_step: Option<f64>,
+    max_steps: usize,
+) -> PyResult<(Py<PyArray1<f64>>, Py<PyDict>)> {
+    let edges = validate_network(
+        temperatures.as_array(),
+        capacities.as_array(),
+        parameters.as_array(),
+        connections.as_array(),
+        edge_types.as_array(),
+    )?;
+    let mut temperatures = temperatures.as_array().to_vec();
+    let capacities = capacities.as_array().to_vec();
+
+    let fixed = fixed_nodes(&capacities);
+
+    let stats = integrate_dopri5(
+        &edges,
+        &capacities,
+        &fixed,
+        &mut temperatures,
+        t_end,
+        atol,
+        rtol,
+        first_step,
+        max_steps,
+    )
+    .map_err(|e| with_temperatures(e.into(), &temperatures))?;
+
+    let array = PyArray1::from_vec(py, temperatures);
+    Ok((array.to_owned(), stats.to_dict(py)?.into()))
+}
+
+#[pyfunction(tol = "1e-8", max_iter = "100")]
+/// Solve for the equilibrium temperatures of the network.
+///
+/// Newton-iterates the heat balance of every node, including the T^4
+/// radiation terms. Nodes with infinite capacity keep their temperature and
+/// act as fixed boundaries; at least one is required.
+///
+/// Parameters
+/// ----------
+/// temperatures : ndarray of shape (N, )
+///     Initial guess, and the prescribed temperatures of fixed nodes.
+/// capacities : ndarray of shape (N, )
+///     Heat capacities for each node (only used to find fixed nodes).
+/// parameters : ndarray of shape (E, )
+///     Parameters for each edge (depending on the edge type).
+/// connections : ndarray of shape (E, 2)
+///     Each row represents an edge, giving the two connected node indices.
+/// edge_types : ndarray of shape (E, )
+///     Integer codes defining the type of each edge (0: Transfer, 1: Radiation, 2: HeatInput, 3: Convection, 4: Advection).
+/// tol : float, optional
+///     Norm of the net heat flow into the free nodes [W] at which the iteration stops.
+///     Default is 1e-8.
+/// max_iter : int, optional
+///     Maximum number of Newton iterations. Default is 100.
+///
+/// Returns
+/// -------
+/// ndarray of shape (N, )
+///     The equilibrium temperatures.
+/// dict
+///     Convergence report with keys 'iterations' and 'residual_norm'.
+#[allow(clippy::too_many_arguments)]
+fn solve_steady_state(
+    py: Python,
+    temperatures: PyReadonlyArray1<f64>,
+    capacities: PyReadonlyArray1<f64>,
+    parameters: PyReadonlyArray1<f64>,
+    connections: PyReadonlyArray2<usize>,
+    edge_types: PyReadonlyArray1<i32>,
+    tol: f64,
+    max_iter: usize,
+) -> PyResult<(Py<PyArray1<f64>>, Py<PyDict>)> {
+    let edges = validate_network(
+        temperatures.as_array(),
+        capacities.as_array(),
+        parameters.as_array(),
+        connections.as_array(),
+        edge_types.as_array(),
+    )?;
+    let mut temperatures = temperatures.as_array().to_vec();
+    let capacities = capacities.as_array().to_vec();
+
+    let fixed = fixed_nodes(&capacities);
+
+    let report = newton_steady_state(&edges, &capacities, &fixed, &mut temperatures, tol, max_iter)?;
+
+    let array = PyArray1::from_vec(py, temperatures);
+    Ok((array.to_owned(), report.to_dict(py)?.into()))
+}
+
+#[pyfunction]
+/// Estimate the largest stable time step of the explicit Euler scheme.
+///
+/// Each node limits dt to C_i / sum_j G_ij over its edges, where G is 1 / R
+/// for Transfer edges and 4 * parameter * T^3 for Radiation edges linearized
+/// at the given temperatures. Nodes with infinite capacity are ignored.
+///
+/// Parameters
+/// ----------
+/// temperatures : ndarray of shape (N, )
+///     Current temperatures of each node.
+/// capacities : ndarray of shape (N, )
+///     Heat capacities for each node.
+/// parameters : ndarray of shape (E, )
+///     Parameters for each edge (depending on the edge type).
+/// connections : ndarray of shape (E, 2)
+///     Each row represents an edge, giving the two connected node indices.
+/// edge_types : ndarray of shape (E, )
+///     Integer codes defining the type of each edge (0: Transfer, 1: Radiation, 2: HeatInput, 3: Convection, 4: Advection).
+///
+/// Returns
+/// -------
+/// float
+///     The maximum stable time step (inf if no node limits it).
+fn stable_time_step(
+    temperatures: PyReadonlyArray1<f64>,
+    capacities: PyReadonlyArray1<f64>,
+    parameters: PyReadonlyArray1<f64>,
+    connections: PyReadonlyArray2<usize>,
+    edge_types: PyReadonlyArray1<i32>,
+) -> PyResult<f64> {
+    let edges = validate_network(
+        temperatures.as_array(),
+        capacities.as_array(),
+        parameters.as_array(),
+        connections.as_array(),
+        edge_types.as_array(),
+    )?;
+    let capacities = capacities.as_array().to_vec();
+    let (dt, _node) = euler_stable_time_step(
+        &edges,
+        &capacities,
+        &fixed_nodes(&capacities),
+        &temperatures.as_array().to_vec(),
+    );
+    Ok(dt)
+}
+
 #[pymodule]
-fn chill(_py: Python, m: &PyModule) -> PyResult<()> {
+fn chill(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<Network>()?;
+    m.add_class::<Profile>()?;
+    m.add_class::<Thermostat>()?;
+    m.add_class::<PidController>()?;
+    m.add_class::<Fluid>()?;
+    m.add_class::<Convection>()?;
     m.add_function(wrap_pyfunction!(process, m)?)?;
+    m.add_function(wrap_pyfunction!(process_history, m)?)?;
+    m.add_function(wrap_pyfunction!(process_implicit, m)?)?;
+    m.add_function(wrap_pyfunction!(process_adaptive, m)?)?;
+    m.add_function(wrap_pyfunction!(solve_steady_state, m)?)?;
+    m.add_function(wrap_pyfunction!(stable_time_step, m)?)?;
+    errors::register(py, m)?;
     Ok(())
 }
 
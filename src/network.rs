@@ -0,0 +1,1563 @@
+// The pyo3 0.16 #[pymethods] expansion trips this rustc lint.
+#![allow(non_local_definitions)]
+
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyArray1, PyReadonlyArray1, PyReadonlyArray2};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::adaptive::{integrate_dopri5, AdaptiveStats};
+use crate::control::Controller;
+use crate::convection::Convection;
+use crate::edge::{edge_flows, heat_balance, Edge, EdgeType, NATURAL_CONVECTION_EXPONENT};
+use crate::energy::EnergyBalance;
+use crate::errors::{invalid_value, with_temperatures, ErrorInfo, StepError};
+use crate::guard::{FailureMode, Guard, MAX_DELTA_TEMPERATURE};
+use crate::implicit::ThetaStepper;
+use crate::integrator::{euler_step, fixed_nodes, stable_time_step as euler_stable_time_step, Method, Rk4, StepLimit};
+use crate::profile::{Profile, Schedule};
+use crate::property::{Conductivity, ConductivityMode, HeatCapacity, PhaseChange};
+use crate::steady::{free_norm, solve_steady_state as newton_steady_state, SteadyStateError, SteadyStateReport};
+use crate::validation::{capacity_problem, damping_problem, duration_problem, parameter_problem, temperature_problem, time_step_problem, validate_network};
+
+/// Per-method state kept between steps.
+enum Stepper {
+    Euler { rates: Vec<f64> },
+    Rk4(Rk4),
+    Theta(ThetaStepper),
+}
+
+/// A node whose temperature is prescribed rather than integrated.
+struct Boundary {
+    node: usize,
+    schedule: Schedule,
+}
+
+/// A step that failed while the network runs with `on_failure='stop'`.
+#[derive(Debug)]
+pub struct Failure {
+    /// Step of the call that failed, counted from 0.
+    pub step: usize,
+    /// Simulated time at which the network stopped.
+    pub time: f64,
+    pub error: StepError,
+}
+
+impl Failure {
+    /// Report as a dict with 'step', 'time', 'node_index', 'value' and 'message'.
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let (message, node_index, value) = self.error.describe();
+        let dict = PyDict::new(py);
+        dict.set_item("step", self.step)?;
+        dict.set_item("time", self.time)?;
+        dict.set_item("node_index", node_index)?;
+        dict.set_item("value", value)?;
+        dict.set_item("message", message)?;
+        Ok(dict)
+    }
+}
+
+/// A thermal network compiled once from the node and edge arrays.
+///
+/// The arrays are validated and the edges decoded on construction, and the
+/// integrator keeps its buffers between calls, so stepping a `Network`
+/// repeatedly does not pay any setup cost.
+///
+/// Parameters
+/// ----------
+/// temperatures : ndarray of shape (N, )
+///     Initial temperatures of each node.
+/// capacities : ndarray of shape (N, )
+///     Heat capacities for each node.
+/// parameters : ndarray of shape (E, )
+///     Parameters for each edge (depending on the edge type).
+/// connections : ndarray of shape (E, 2)
+///     Each row represents an edge, giving the two connected node indices.
+/// edge_types : ndarray of shape (E, )
+///     Integer codes defining the type of each edge (0: Transfer, 1: Radiation, 2: HeatInput, 3: Convection, 4: Advection).
+/// dt : float
+///     Time step for the simulation, finite and positive.
+/// method : str, optional
+///     Time integration scheme, see `process`. Default is 'euler'.
+/// damping : float, optional
+///     Off-centering of Crank–Nicolson, see `process`. Default is 0.
+/// dt_limit : str, optional
+///     Handling of the stable time step, see `process`. Default is 'none'.
+/// edge_flows : bool, optional
+///     Accumulate the heat flow through each edge so that `mean_edge_flows`
+///     is available. Default is False.
+/// energy_balance : bool, optional
+///     Keep the energy bookkeeping reported by `energy_balance`. Default is False.
+/// max_delta : float, optional
+///     Largest temperature change of a node in a single step [K]. Default is 1e8.
+/// temperature_range : (float, float), optional
+///     Allowed (min, max) temperature [K]. Default is None (unbounded).
+/// check_finite : bool, optional
+///     Treat NaN and infinite temperatures as a failure. Default is True.
+/// on_failure : str, optional
+///     What to do when a step fails one of the checks above, exceeds the
+///     stable limit or its linear solve fails: 'raise' (default) or 'stop'.
+///     Either way the network keeps the state before the failing step;
+///     'stop' reports the failure in `failure` instead of raising.
+/// min_dt : float, optional
+///     Enables step halving: a failing step is rolled back and redone as
+///     two steps of half the size, recursively, down to `min_dt`. Only a
+///     failure at `min_dt` is handled by `on_failure`. Default is None
+///     (disabled).
+#[pyclass(module = "chill")]
+pub struct Network {
+    temperatures: Vec<f64>,
+    capacities: Vec<f64>,
+    /// Nodes held out of the integration: the boundaries and the nodes of
+    /// infinite capacity.
+    fixed: Vec<bool>,
+    edges: Vec<Edge>,
+    dt: f64,
+    time: f64,
+    method: Method,
+    damping: f64,
+    dt_limit: StepLimit,
+    stepper: Stepper,
+    /// Heat flow through each edge [W], scratch space.
+    flows: Vec<f64>,
+    /// Energy through each edge since the averaging started [J].
+    flow_totals: Option<Vec<f64>>,
+    flow_time: f64,
+    energy: Option<EnergyBalance>,
+    guard: Guard,
+    on_failure: FailureMode,
+    /// Temperatures before the current step, restored when it fails.
+    previous: Vec<f64>,
+    failure: Option<Failure>,
+    /// Smallest step of the step halving, None if it is disabled.
+    min_dt: Option<f64>,
+    halvings: usize,
+    boundaries: Vec<Boundary>,
+    /// Power schedules of HeatInput edges as (edge, schedule).
+    heat_inputs: Vec<(usize, Schedule)>,
+    /// Controllers driving HeatInput edges, at most one per edge.
+    controllers: Vec<Controller>,
+    /// Temperature-dependent conductances of Transfer edges as (edge, conductivity).
+    conductivities: Vec<(usize, Conductivity)>,
+    /// Correlations of Convection edges that depend on the temperatures.
+    convections: Vec<(usize, Convection)>,
+    /// Mass flow schedules of Advection edges as (edge, mass flow, specific heat).
+    mass_flows: Vec<(usize, Schedule, f64)>,
+    /// Temperature-dependent capacities of nodes as (node, heat capacity).
+    heat_capacities: Vec<(usize, HeatCapacity)>,
+}
+
+/// Time and temperatures (and optionally edge flows) recorded during a run.
+pub struct Recording {
+    pub times: Vec<f64>,
+    pub temperatures: Array2<f64>,
+    pub edge_flows: Option<Array2<f64>>,
+}
+
+impl Recording {
+    /// (times, temperatures) or (times, temperatures, edge_flows).
+    pub fn into_py(self, py: Python) -> PyObject {
+        let times = PyArray1::from_vec(py, self.times);
+        let temperatures = self.temperatures.into_pyarray(py);
+        match self.edge_flows {
+            Some(flows) => (times, temperatures, flows.into_pyarray(py)).into_py(py),
+            None => (times, temperatures).into_py(py),
+        }
+    }
+}
+
+/// `ChillValidationError` for an invalid parameter of `edge`.
+fn invalid_parameter(edge: usize, parameter: f64, message: String) -> PyErr {
+    invalid_value(
+        format!("edge {}: {}", edge, message),
+        ErrorInfo {
+            edge_index: Some(edge),
+            value: Some(parameter),
+            ..ErrorInfo::default()
+        },
+    )
+}
+
+/// `ChillValidationError` for an edge index past the last edge.
+fn edge_out_of_range(edge: usize) -> PyErr {
+    invalid_value(
+        format!("Edge index {} out of range", edge),
+        ErrorInfo {
+            edge_index: Some(edge),
+            ..ErrorInfo::default()
+        },
+    )
+}
+
+/// `ChillValidationError` for a node index past the last node.
+fn node_out_of_range(node: usize) -> PyErr {
+    invalid_value(
+        format!("Node index {} out of range", node),
+        ErrorInfo {
+            node_index: Some(node),
+            ..ErrorInfo::default()
+        },
+    )
+}
+
+/// `ChillValidationError` for an edge whose type does not take the setting.
+fn wrong_edge_type(edge: usize, expected: &str) -> PyErr {
+    invalid_value(
+        format!("Edge {} is not {} edge", edge, expected),
+        ErrorInfo {
+            edge_index: Some(edge),
+            ..ErrorInfo::default()
+        },
+    )
+}
+
+/// `ChillValidationError` for the problem found with `value`, if any.
+fn check_value(problem: Option<String>, value: f64) -> PyResult<()> {
+    match problem {
+        Some(message) => Err(invalid_value(
+            message,
+            ErrorInfo {
+                value: Some(value),
+                ..ErrorInfo::default()
+            },
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Put a Convection edge back on the default law h ∝ |ΔT|^0.25 once its
+/// power law or correlation is dropped.
+fn restore_exponent(edge: &mut Edge) {
+    if let EdgeType::Convection { .. } = edge.edge_type {
+        edge.edge_type = EdgeType::Convection {
+            exponent: NATURAL_CONVECTION_EXPONENT,
+        };
+    }
+}
+
+/// Capacity rate ṁ * cp [W/K] of an Advection edge at `time`.
+fn capacity_rate(mass_flow: &Schedule, specific_heat: f64, time: f64) -> PyResult<f64> {
+    let flow = mass_flow.value_at(time)?;
+    if flow.is_nan() || flow < 0.0 {
+        return Err(invalid_value(
+            format!("mass flow must not be negative, got {} at t = {}", flow, time),
+            ErrorInfo {
+                value: Some(flow),
+                time: Some(time),
+                ..ErrorInfo::default()
+            },
+        ));
+    }
+    Ok(flow * specific_heat)
+}
+
+impl Network {
+    pub fn build(
+        temperatures: Vec<f64>,
+        capacities: Vec<f64>,
+        edges: Vec<Edge>,
+        dt: f64,
+        method: Method,
+        damping: f64,
+        dt_limit: StepLimit,
+    ) -> PyResult<Self> {
+        check_value(time_step_problem(dt), dt)?;
+        check_value(damping_problem(damping), damping)?;
+        let fixed = fixed_nodes(&capacities);
+        let stepper = Self::make_stepper(&fixed, &edges, method, damping);
+        let flows = vec![0.0; edges.len()];
+        let previous = temperatures.clone();
+        Ok(Network {
+            temperatures,
+            capacities,
+            fixed,
+            edges,
+            dt,
+            time: 0.0,
+            method,
+            damping,
+            dt_limit,
+            stepper,
+            flows,
+            flow_totals: None,
+            flow_time: 0.0,
+            energy: None,
+            guard: Guard::default(),
+            on_failure: FailureMode::Raise,
+            previous,
+            failure: None,
+            min_dt: None,
+            halvings: 0,
+            boundaries: Vec::new(),
+            heat_inputs: Vec::new(),
+            controllers: Vec::new(),
+            conductivities: Vec::new(),
+            convections: Vec::new(),
+            mass_flows: Vec::new(),
+            heat_capacities: Vec::new(),
+        })
+    }
+
+    /// Let the power of the HeatInput edge `edge` follow `schedule`.
+    pub fn set_heat_input(&mut self, edge: usize, schedule: Schedule) -> PyResult<()> {
+        let slot = self.edges.get_mut(edge).ok_or_else(|| edge_out_of_range(edge))?;
+        if slot.edge_type != EdgeType::HeatInput {
+            return Err(wrong_edge_type(edge, "a HeatInput"));
+        }
+        slot.parameter = schedule.value_at(self.time)?;
+        self.release_edge(edge);
+        self.heat_inputs.push((edge, schedule));
+        self.refresh_energy();
+        Ok(())
+    }
+
+    /// Let `controller` drive the power of its HeatInput edge.
+    pub fn add_controller(&mut self, controller: Controller) -> PyResult<()> {
+        let edge = controller.edge();
+        match self.edges.get(edge) {
+            None => return Err(edge_out_of_range(edge)),
+            Some(slot) if slot.edge_type != EdgeType::HeatInput => return Err(wrong_edge_type(edge, "a HeatInput")),
+            _ => {}
+        }
+        if controller.sensor() >= self.temperatures.len() {
+            return Err(node_out_of_range(controller.sensor()));
+        }
+        self.release_edge(edge);
+        self.controllers.push(controller);
+        self.update_controllers();
+        self.refresh_energy();
+        Ok(())
+    }
+
+    /// Reports of the controllers, see `Controller::to_dict`.
+    pub fn controller_reports<'py>(&self, py: Python<'py>) -> PyResult<Vec<&'py PyDict>> {
+        self.controllers.iter().map(|controller| controller.to_dict(py)).collect()
+    }
+
+    /// Let the resistance of the Transfer edge `edge` follow `conductivity`,
+    /// evaluated at the temperatures of its nodes at every step.
+    pub fn set_conductivity(&mut self, edge: usize, conductivity: Conductivity) -> PyResult<()> {
+        let slot = self.edges.get_mut(edge).ok_or_else(|| edge_out_of_range(edge))?;
+        if slot.edge_type != EdgeType::Transfer {
+            return Err(wrong_edge_type(edge, "a Transfer"));
+        }
+        slot.parameter = conductivity.resistance(self.temperatures[slot.n1], self.temperatures[slot.n2])?;
+        self.release_edge(edge);
+        self.conductivities.push((edge, conductivity));
+        self.refresh_energy();
+        Ok(())
+    }
+
+    /// Let the Convection edge `edge` follow `convection`: a power law with
+    /// its own exponent, or a correlation evaluated at every step.
+    pub fn set_convection(&mut self, edge: usize, convection: Convection) -> PyResult<()> {
+        let slot = self.edges.get_mut(edge).ok_or_else(|| edge_out_of_range(edge))?;
+        if !matches!(slot.edge_type, EdgeType::Convection { .. }) {
+            return Err(wrong_edge_type(edge, "a Convection"));
+        }
+        let parameter = convection.parameter(self.temperatures[slot.n1], self.temperatures[slot.n2])?;
+        self.release_edge(edge);
+        let slot = &mut self.edges[edge];
+        slot.parameter = parameter;
+        slot.edge_type = EdgeType::Convection {
+            exponent: convection.exponent(),
+        };
+        if !convection.is_constant() {
+            self.convections.push((edge, convection));
+        }
+        self.refresh_energy();
+        Ok(())
+    }
+
+    /// Let the mass flow of the Advection edge `edge` follow `mass_flow`, so
+    /// that the edge carries ṁ(t) * cp from its first node into its second.
+    pub fn set_mass_flow(&mut self, edge: usize, mass_flow: Schedule, specific_heat: f64) -> PyResult<()> {
+        let slot = self.edges.get(edge).ok_or_else(|| edge_out_of_range(edge))?;
+        if slot.edge_type != EdgeType::Advection {
+            return Err(wrong_edge_type(edge, "an Advection"));
+        }
+        if !specific_heat.is_finite() || specific_heat <= 0.0 {
+            return Err(invalid_parameter(
+                edge,
+                specific_heat,
+                format!("specific heat must be positive, got {}", specific_heat),
+            ));
+        }
+        let rate = capacity_rate(&mass_flow, specific_heat, self.time)?;
+        self.edges[edge].parameter = rate;
+        self.release_edge(edge);
+        self.mass_flows.push((edge, mass_flow, specific_heat));
+        self.refresh_energy();
+        Ok(())
+    }
+
+    /// Drop the power profile, controller, conductivity, convection
+    /// correlation or mass flow of `edge`.
+    fn release_edge(&mut self, edge: usize) {
+        restore_exponent(&mut self.edges[edge]);
+        self.heat_inputs.retain(|(e, _)| *e != edge);
+        self.controllers.retain(|c| c.edge() != edge);
+        self.conductivities.retain(|(e, _)| *e != edge);
+        self.convections.retain(|(e, _)| *e != edge);
+        self.mass_flows.retain(|(e, _, _)| *e != edge);
+    }
+
+    /// Set the parameters of the temperature-dependent edges from the current temperatures.
+    fn update_conductances(&mut self) -> Result<(), StepError> {
+        if self.conductivities.is_empty() && self.convections.is_empty() {
+            return Ok(());
+        }
+        for (edge, conductivity) in &self.conductivities {
+            let edge = &mut self.edges[*edge];
+            edge.parameter = conductivity
+                .resistance(self.temperatures[edge.n1], self.temperatures[edge.n2])
+                .map_err(StepError::Python)?;
+        }
+        for (edge, convection) in &self.convections {
+            let edge = &mut self.edges[*edge];
+            edge.parameter = convection
+                .parameter(self.temperatures[edge.n1], self.temperatures[edge.n2])
+                .map_err(StepError::Python)?;
+        }
+        self.refresh_energy();
+        Ok(())
+    }
+
+    /// Let the controllers set the power of their edges from the current state.
+    fn update_controllers(&mut self) {
+        for controller in &mut self.controllers {
+            self.edges[controller.edge()].parameter = controller.update(self.time, &self.temperatures);
+        }
+    }
+
+    /// Prescribe the temperature of `node`.
+    ///
+    /// The node is held out of the integration and takes the temperature of
+    /// `schedule` at every step. It keeps its capacity for when it is cleared.
+    pub fn set_boundary(&mut self, node: usize, schedule: Schedule) -> PyResult<()> {
+        if node >= self.temperatures.len() {
+            return Err(node_out_of_range(node));
+        }
+        self.temperatures[node] = schedule.value_at(self.time)?;
+        if self.release_node(node) {
+            self.reset_energy();
+        }
+        match self.boundaries.iter_mut().find(|b| b.node == node) {
+            Some(boundary) => boundary.schedule = schedule,
+            None => {
+                self.boundaries.push(Boundary { node, schedule });
+                self.fixed_nodes_changed();
+            }
+        }
+        Ok(())
+    }
+
+    /// Let a boundary node be integrated again with its former capacity.
+    pub fn clear_boundary(&mut self, node: usize) -> PyResult<()> {
+        let index = self
+            .boundaries
+            .iter()
+            .position(|b| b.node == node)
+            .ok_or_else(|| {
+                invalid_value(
+                    format!("Node {} is not a boundary node", node),
+                    ErrorInfo {
+                        node_index: Some(node),
+                        ..ErrorInfo::default()
+                    },
+                )
+            })?;
+        self.boundaries.swap_remove(index);
+        self.fixed_nodes_changed();
+        Ok(())
+    }
+
+    /// Set the boundary temperatures, heat input powers and mass flows to their value at `time`.
+    fn apply_schedules(&mut self, time: f64) -> Result<(), StepError> {
+        for boundary in &self.boundaries {
+            self.temperatures[boundary.node] = boundary.schedule.value_at(time).map_err(StepError::Python)?;
+        }
+        for (edge, schedule) in &self.heat_inputs {
+            self.edges[*edge].parameter = schedule.value_at(time).map_err(StepError::Python)?;
+        }
+        for (edge, mass_flow, specific_heat) in &self.mass_flows {
+            self.edges[*edge].parameter =
+                capacity_rate(mass_flow, *specific_heat, time).map_err(StepError::Python)?;
+        }
+        Ok(())
+    }
+
+    /// Rebuild what depends on which nodes are fixed.
+    fn fixed_nodes_changed(&mut self) {
+        self.fixed = fixed_nodes(&self.capacities);
+        for boundary in &self.boundaries {
+            self.fixed[boundary.node] = true;
+        }
+        // fixed nodes shape the implicit system
+        self.stepper = Self::make_stepper(&self.fixed, &self.edges, self.method, self.damping);
+        self.reset_energy();
+    }
+
+    /// Let the capacity of the free node `node` depend on its temperature.
+    pub fn set_heat_capacity(&mut self, node: usize, heat_capacity: HeatCapacity) -> PyResult<()> {
+        if node >= self.temperatures.len() {
+            return Err(node_out_of_range(node));
+        }
+        if self.fixed[node] {
+            return Err(invalid_value(
+                format!("Node {} is fixed, its capacity cannot depend on temperature", node),
+                ErrorInfo {
+                    node_index: Some(node),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        self.capacities[node] = heat_capacity.value_at(self.temperatures[node])?;
+        self.release_node(node);
+        self.heat_capacities.push((node, heat_capacity));
+        self.reset_energy();
+        Ok(())
+    }
+
+    /// Let `node` melt and freeze, keeping its sensible heat capacity: the
+    /// temperature-dependent one if it has one, otherwise its constant capacity.
+    pub fn set_phase_change(&mut self, node: usize, phase_change: PhaseChange) -> PyResult<()> {
+        if node >= self.temperatures.len() {
+            return Err(node_out_of_range(node));
+        }
+        let heat_capacity = match self.heat_capacities.iter().position(|(n, _)| *n == node) {
+            Some(index) => self.heat_capacities.swap_remove(index).1,
+            None => HeatCapacity::new(Schedule::Table(Profile::constant(self.capacities[node])))?,
+        };
+        self.set_heat_capacity(node, heat_capacity.with_phase_change(phase_change))
+    }
+
+    /// Liquid fraction of each node, NaN for the nodes without a phase change.
+    pub fn melt_fractions(&self) -> Vec<f64> {
+        let mut fractions = vec![f64::NAN; self.temperatures.len()];
+        for (node, heat_capacity) in &self.heat_capacities {
+            if let Some(fraction) = heat_capacity.melt_fraction(self.temperatures[*node]) {
+                fractions[*node] = fraction;
+            }
+        }
+        fractions
+    }
+
+    /// Drop the temperature-dependent capacity of `node`; true if it had one.
+    fn release_node(&mut self, node: usize) -> bool {
+        let before = self.heat_capacities.len();
+        self.heat_capacities.retain(|(n, _)| *n != node);
+        self.heat_capacities.len() != before
+    }
+
+    /// Set the capacity of the temperature-dependent nodes from the current temperatures.
+    fn update_capacities(&mut self) -> Result<(), StepError> {
+        for (node, heat_capacity) in &self.heat_capacities {
+            self.capacities[*node] = heat_capacity
+                .value_at(self.temperatures[*node])
+                .map_err(StepError::Python)?;
+        }
+        Ok(())
+    }
+
+    /// Turn the energy each temperature-dependent node received in the step,
+    /// C * ΔT with the capacity used by the step, into its temperature on
+    /// the enthalpy curve. Returns the total enthalpy change [J].
+    fn apply_enthalpy(&mut self) -> Result<f64, StepError> {
+        let mut total = 0.0;
+        for (node, heat_capacity) in &self.heat_capacities {
+            let start = self.previous[*node];
+            let energy = self.capacities[*node] * (self.temperatures[*node] - start);
+            self.temperatures[*node] = heat_capacity
+                .temperature_after(start, energy)
+                .map_err(StepError::Python)?;
+            total += energy;
+        }
+        Ok(total)
+    }
+
+    /// Enable step halving down to `min_dt`, or disable it with None.
+    pub fn set_step_halving(&mut self, min_dt: Option<f64>) -> PyResult<()> {
+        if let Some(min_dt) = min_dt {
+            if min_dt.is_nan() || min_dt <= 0.0 {
+                return Err(invalid_value(
+                    format!("min_dt must be positive, got {}", min_dt),
+                    ErrorInfo {
+                        value: Some(min_dt),
+                        ..ErrorInfo::default()
+                    },
+                ));
+            }
+        }
+        self.min_dt = min_dt;
+        Ok(())
+    }
+
+    /// Number of times a step was halved since construction.
+    pub fn halvings(&self) -> usize {
+        self.halvings
+    }
+
+    /// Checks applied after every step and what to do when one fails.
+    pub fn set_guard(&mut self, guard: Guard, on_failure: FailureMode) {
+        self.guard = guard;
+        self.on_failure = on_failure;
+    }
+
+    /// The failure that stopped the last call, if any.
+    pub fn failure(&self) -> Option<&Failure> {
+        self.failure.as_ref()
+    }
+
+    /// Start (or restart) the energy bookkeeping at the current state.
+    pub fn track_energy(&mut self) {
+        let enthalpy_nodes = self.heat_capacities.iter().map(|(node, _)| *node).collect();
+        self.energy = Some(EnergyBalance::new(
+            &self.edges,
+            &self.capacities,
+            &self.fixed,
+            &self.temperatures,
+            enthalpy_nodes,
+        ));
+    }
+
+    /// Restart the energy bookkeeping if it is kept.
+    fn reset_energy(&mut self) {
+        if self.energy.is_some() {
+            self.track_energy();
+        }
+    }
+
+    /// Energy bookkeeping since `track_energy`, as a dict, or None if it is not kept.
+    pub fn energy_report<'py>(&self, py: Python<'py>) -> PyResult<Option<&'py PyDict>> {
+        self.energy
+            .as_ref()
+            .map(|energy| energy.to_dict(py, &self.capacities, &self.temperatures))
+            .transpose()
+    }
+
+    /// Keep the bookkeeping consistent after the edges changed.
+    fn refresh_energy(&mut self) {
+        if let Some(energy) = &mut self.energy {
+            energy.refresh(&self.edges, &self.temperatures);
+        }
+    }
+
+    /// Start (or restart) averaging the heat flow through each edge.
+    pub fn track_edge_flows(&mut self) {
+        self.flow_totals = Some(vec![0.0; self.edges.len()]);
+        self.flow_time = 0.0;
+    }
+
+    /// Heat flow through each edge at the current temperatures [W].
+    pub fn current_edge_flows(&mut self) -> &[f64] {
+        edge_flows(&self.edges, &self.temperatures, &mut self.flows);
+        &self.flows
+    }
+
+    /// Heat flow through each edge averaged over the steps since
+    /// `track_edge_flows` [W], or None if the flows are not tracked.
+    ///
+    /// Each step contributes the flows it applied: those at its start for
+    /// Euler, the weighted stage flows for RK4 and the flows linearized to
+    /// T + theta * dT for the implicit schemes. Flow times time is then the
+    /// energy the edge carried.
+    pub fn mean_edge_flows(&mut self) -> Option<Vec<f64>> {
+        let flow_time = self.flow_time;
+        if flow_time == 0.0 {
+            let current = self.current_edge_flows().to_vec();
+            return self.flow_totals.as_ref().map(|_| current);
+        }
+        self.flow_totals
+            .as_ref()
+            .map(|totals| totals.iter().map(|e| e / flow_time).collect())
+    }
+
+    /// Heat flow through each edge that the last step of `dt` applied [W],
+    /// so that flow * dt is the energy exchanged in the step.
+    fn step_edge_flows(&mut self, dt: f64) {
+        match &mut self.stepper {
+            Stepper::Euler { .. } => edge_flows(&self.edges, &self.previous, &mut self.flows),
+            Stepper::Rk4(rk4) => rk4.edge_flows(&self.edges, &self.previous, dt, &mut self.flows),
+            Stepper::Theta(theta) => theta.edge_flows(&self.edges, &self.previous, &mut self.flows),
+        }
+    }
+
+    fn make_stepper(fixed: &[bool], edges: &[Edge], method: Method, damping: f64) -> Stepper {
+        match (method, method.theta(damping)) {
+            (_, Some(theta)) => Stepper::Theta(ThetaStepper::new(fixed, edges, theta)),
+            (Method::Rk4, None) => Stepper::Rk4(Rk4::new(fixed.len())),
+            _ => Stepper::Euler {
+                rates: vec![0.0; fixed.len()],
+            },
+        }
+    }
+
+    /// Advance the network by `steps` steps of `dt`.
+    ///
+    /// With `on_failure='stop'`, a failing step ends the call early and is
+    /// reported by `failure`.
+    pub fn advance(&mut self, steps: usize) -> PyResult<()> {
+        self.failure = None;
+        for i in 0..steps {
+            if !self.step_or_stop(i)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Take step `step` of the call; false if the network stopped.
+    ///
+    /// A failing step leaves the last good state behind in either mode.
+    fn step_or_stop(&mut self, step: usize) -> PyResult<bool> {
+        match self.step_once() {
+            Ok(()) => Ok(true),
+            // errors of schedule functions are not numerical failures
+            Err(StepError::Python(error)) => Err(error),
+            Err(error) if self.on_failure == FailureMode::Stop => {
+                self.failure = Some(Failure {
+                    step,
+                    time: self.time,
+                    error,
+                });
+                Ok(false)
+            }
+            Err(error) => Err(error.into_py_err(step, self.time, &self.temperatures)),
+        }
+    }
+
+    /// Advance the network by `steps` steps, recording the time and the
+    /// temperatures after every `record_every` steps, and with `with_flows`
+    /// the heat flow through each edge at the same instants.
+    ///
+    /// The histories have n_records = steps / record_every rows.
+    pub fn advance_recording(&mut self, steps: usize, record_every: usize, with_flows: bool) -> PyResult<Recording> {
+        if record_every == 0 {
+            return Err(invalid_value(
+                "record_every must be at least 1".to_string(),
+                ErrorInfo {
+                    value: Some(0.0),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        let n_records = steps / record_every;
+        let mut times = Vec::with_capacity(n_records);
+        let mut temperatures = Vec::with_capacity(n_records * self.temperatures.len());
+        let mut flows = Vec::with_capacity(if with_flows { n_records * self.edges.len() } else { 0 });
+        self.failure = None;
+        for i in 0..steps {
+            if !self.step_or_stop(i)? {
+                break;
+            }
+            if (i + 1) % record_every == 0 {
+                times.push(self.time);
+                temperatures.extend_from_slice(&self.temperatures);
+                if with_flows {
+                    flows.extend_from_slice(self.current_edge_flows());
+                }
+            }
+        }
+        let n_records = times.len();
+        Ok(Recording {
+            temperatures: Array2::from_shape_vec((n_records, self.temperatures.len()), temperatures).unwrap(),
+            edge_flows: with_flows.then(|| Array2::from_shape_vec((n_records, self.edges.len()), flows).unwrap()),
+            times,
+        })
+    }
+
+    fn step_once(&mut self) -> Result<(), StepError> {
+        self.step_halving(self.dt)
+    }
+
+    /// Step over an interval of `dt`. With step halving enabled, a failure
+    /// rolls back the failing step and redoes the rest of the interval as two
+    /// halves, recursively, as long as the halves are not below `min_dt`.
+    fn step_halving(&mut self, dt: f64) -> Result<(), StepError> {
+        let start = self.time;
+        match self.step_interval(dt) {
+            Err(error) => {
+                let remaining = dt - (self.time - start);
+                match self.min_dt {
+                    Some(min_dt) if 0.5 * remaining >= min_dt && !matches!(error, StepError::Python(_)) => {
+                        self.halvings += 1;
+                        self.step_halving(0.5 * remaining)?;
+                        self.step_halving(0.5 * remaining)
+                    }
+                    _ => Err(error),
+                }
+            }
+            ok => ok,
+        }
+    }
+
+    fn step_interval(&mut self, dt: f64) -> Result<(), StepError> {
+        let (substeps, sub_dt) = match self.stepper {
+            Stepper::Theta(_) => (1, dt),
+            _ => self
+                .dt_limit
+                .substeps(&self.edges, &self.capacities, &self.fixed, &self.temperatures, dt)?,
+        };
+        // implicit steps see the schedules at t + theta * dt
+        let schedule_offset = match self.stepper {
+            Stepper::Theta(ref theta) => theta.theta() * sub_dt,
+            _ => 0.0,
+        };
+
+        for _j in 0..substeps {
+            self.apply_schedules(self.time + schedule_offset)?;
+            self.update_controllers();
+            self.update_conductances()?;
+            self.update_capacities()?;
+            self.previous.copy_from_slice(&self.temperatures);
+            let stepped = match &mut self.stepper {
+                Stepper::Euler { rates } => {
+                    euler_step(
+                        &self.edges,
+                        &self.capacities,
+                        &self.fixed,
+                        &mut self.temperatures,
+                        sub_dt,
+                        rates,
+                    );
+                    Ok(())
+                }
+                Stepper::Rk4(rk4) => {
+                    rk4.step(&self.edges, &self.capacities, &self.fixed, &mut self.temperatures, sub_dt);
+                    Ok(())
+                }
+                Stepper::Theta(theta) => theta
+                    .step(&self.edges, &self.capacities, &mut self.temperatures, sub_dt)
+                    .map(|_| ())
+                    .map_err(StepError::Solver),
+            };
+            let checked = stepped.and_then(|_| self.apply_enthalpy()).and_then(|enthalpy| {
+                self.guard
+                    .check(&self.previous, &self.temperatures, &self.fixed)
+                    .map(|_| enthalpy)
+                    .map_err(StepError::Guard)
+            });
+            let enthalpy = match checked {
+                Ok(enthalpy) => enthalpy,
+                Err(error) => {
+                    self.temperatures.copy_from_slice(&self.previous);
+                    return Err(error);
+                }
+            };
+
+            if self.flow_totals.is_some() {
+                self.step_edge_flows(sub_dt);
+            }
+            if let Some(totals) = &mut self.flow_totals {
+                for (total, flow) in totals.iter_mut().zip(&self.flows) {
+                    *total += flow * sub_dt;
+                }
+                self.flow_time += sub_dt;
+            }
+            for controller in &mut self.controllers {
+                controller.account(sub_dt);
+            }
+            // a failing substep leaves the time at the last good state
+            self.time += sub_dt;
+            self.apply_schedules(self.time)?;
+            if let Some(energy) = &mut self.energy {
+                energy.record_step(&self.edges, &self.temperatures, sub_dt);
+                energy.record_enthalpy(enthalpy);
+            }
+            // the heater powers held over the step were recorded, switch for the next one
+            if !self.controllers.is_empty() {
+                self.update_controllers();
+                self.refresh_energy();
+            }
+        }
+        Ok(())
+    }
+
+    /// What makes the edges or capacities change during a run, beyond what
+    /// the edge types themselves describe.
+    fn varying_features(&self) -> Vec<&'static str> {
+        let mut features = Vec::new();
+        if self.boundaries.iter().any(|b| !b.schedule.is_constant()) {
+            features.push("boundary schedules");
+        }
+        if self.heat_inputs.iter().any(|(_, schedule)| !schedule.is_constant()) {
+            features.push("heat input profiles");
+        }
+        if self.mass_flows.iter().any(|(_, mass_flow, _)| !mass_flow.is_constant()) {
+            features.push("mass flow schedules");
+        }
+        if !self.controllers.is_empty() {
+            features.push("controllers");
+        }
+        if !self.conductivities.is_empty() {
+            features.push("temperature-dependent conductivities");
+        }
+        if !self.convections.is_empty() {
+            features.push("convection correlations");
+        }
+        if !self.heat_capacities.is_empty() {
+            features.push("temperature-dependent capacities");
+        }
+        features
+    }
+
+    /// Largest stable time step of the explicit Euler scheme at the current
+    /// state [s], inf if no node limits it.
+    pub fn stable_time_step(&mut self) -> PyResult<f64> {
+        self.update_conductances()
+            .and_then(|_| self.update_capacities())
+            .map_err(|e| e.into_py_err(0, self.time, &self.temperatures))?;
+        let (dt, _node) = euler_stable_time_step(&self.edges, &self.capacities, &self.fixed, &self.temperatures);
+        Ok(dt)
+    }
+
+    /// Advance the network by `duration` with the adaptive Dormand–Prince
+    /// 5(4) scheme.
+    ///
+    /// The scheme integrates fixed edges and capacities, so a network whose
+    /// schedules, controllers or temperature-dependent properties change them
+    /// is rejected. The energy bookkeeping and the edge flow averaging restart
+    /// at the state reached. A failure leaves the network unchanged.
+    pub fn run_adaptive(&mut self, duration: f64, atol: f64, rtol: f64, max_steps: usize) -> PyResult<AdaptiveStats> {
+        check_value(duration_problem(duration), duration)?;
+        let features = self.varying_features();
+        if !features.is_empty() {
+            return Err(invalid_value(
+                format!(
+                    "The adaptive scheme cannot follow the {} of this network, use step() instead",
+                    features.join(", ")
+                ),
+                ErrorInfo::default(),
+            ));
+        }
+        let mut temperatures = self.temperatures.clone();
+        let stats = integrate_dopri5(
+            &self.edges,
+            &self.capacities,
+            &self.fixed,
+            &mut temperatures,
+            duration,
+            atol,
+            rtol,
+            None,
+            max_steps,
+        )
+        .map_err(|e| with_temperatures(e.into(), &temperatures))?;
+        self.temperatures = temperatures;
+        self.time += duration;
+        self.reset_energy();
+        if self.flow_totals.is_some() {
+            self.track_edge_flows();
+        }
+        Ok(stats)
+    }
+
+    /// Replace the temperatures of the free nodes with the equilibrium at the
+    /// current boundary temperatures, powers and mass flows.
+    ///
+    /// Temperature-dependent conductances are iterated to consistency with
+    /// the equilibrium; capacities do not enter it. The power of a controller
+    /// has no equilibrium value, so a network with controllers is rejected.
+    /// A failure leaves the network unchanged.
+    pub fn steady_state(&mut self, tol: f64, max_iter: usize) -> PyResult<SteadyStateReport> {
+        if !self.controllers.is_empty() {
+            return Err(invalid_value(
+                "The steady state of a network with controllers is undefined".to_string(),
+                ErrorInfo::default(),
+            ));
+        }
+        let temperatures = self.temperatures.clone();
+        let parameters: Vec<f64> = self.edges.iter().map(|edge| edge.parameter).collect();
+        let report = self.iterate_steady_state(tol, max_iter);
+        match report {
+            Ok(_) => self.reset_energy(),
+            Err(_) => {
+                self.temperatures = temperatures;
+                for (edge, parameter) in self.edges.iter_mut().zip(parameters) {
+                    edge.parameter = parameter;
+                }
+                self.refresh_energy();
+            }
+        }
+        report
+    }
+
+    /// Newton-solve the heat balance with the temperature-dependent
+    /// conductances of the current temperatures, until it holds with the
+    /// conductances of the solution.
+    fn iterate_steady_state(&mut self, tol: f64, max_iter: usize) -> PyResult<SteadyStateReport> {
+        self.apply_schedules(self.time)
+            .map_err(|e| e.into_py_err(0, self.time, &self.temperatures))?;
+        let mut flows = vec![0.0; self.temperatures.len()];
+        let mut iterations = 0;
+        loop {
+            self.update_conductances()
+                .map_err(|e| e.into_py_err(0, self.time, &self.temperatures))?;
+            heat_balance(&self.edges, &self.temperatures, &mut flows);
+            let residual_norm = free_norm(&flows, &self.fixed);
+            if residual_norm <= tol {
+                return Ok(SteadyStateReport {
+                    iterations,
+                    residual_norm,
+                });
+            }
+            if iterations == max_iter {
+                return Err(SteadyStateError::NotConverged {
+                    iterations,
+                    residual_norm,
+                }
+                .into());
+            }
+            let report = newton_steady_state(
+                &self.edges,
+                &self.capacities,
+                &self.fixed,
+                &mut self.temperatures,
+                tol,
+                max_iter - iterations,
+            )
+            .map_err(|e| match e {
+                SteadyStateError::NotConverged { residual_norm, .. } => SteadyStateError::NotConverged {
+                    iterations: max_iter,
+                    residual_norm,
+                },
+                e => e,
+            })?;
+            // the residual above is not below tol, so a solve without an iteration is stuck
+            iterations += report.iterations.max(1);
+        }
+    }
+
+    pub fn into_temperatures(self) -> Vec<f64> {
+        self.temperatures
+    }
+}
+
+#[pymethods]
+impl Network {
+    #[new]
+    #[args(
+        method = "\"euler\"",
+        damping = "0.0",
+        dt_limit = "\"none\"",
+        edge_flows = "false",
+        energy_balance = "false",
+        max_delta = "MAX_DELTA_TEMPERATURE",
+        temperature_range = "None",
+        check_finite = "true",
+        on_failure = "\"raise\"",
+        min_dt = "None"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        temperatures: PyReadonlyArray1<f64>,
+        capacities: PyReadonlyArray1<f64>,
+        parameters: PyReadonlyArray1<f64>,
+        connections: PyReadonlyArray2<usize>,
+        edge_types: PyReadonlyArray1<i32>,
+        dt: f64,
+        method: &str,
+        damping: f64,
+        dt_limit: &str,
+        edge_flows: bool,
+        energy_balance: bool,
+        max_delta: f64,
+        temperature_range: Option<(f64, f64)>,
+        check_finite: bool,
+        on_failure: &str,
+        min_dt: Option<f64>,
+    ) -> PyResult<Self> {
+        let edges = validate_network(
+            temperatures.as_array(),
+            capacities.as_array(),
+            parameters.as_array(),
+            connections.as_array(),
+            edge_types.as_array(),
+        )?;
+        let mut network = Network::build(
+            temperatures.as_array().to_vec(),
+            capacities.as_array().to_vec(),
+            edges,
+            dt,
+            Method::from_name(method)?,
+            damping,
+            StepLimit::from_name(dt_limit)?,
+        )?;
+        if edge_flows {
+            network.track_edge_flows();
+        }
+        if energy_balance {
+            network.track_energy();
+        }
+        network.set_guard(
+            Guard::new(max_delta, temperature_range, check_finite)?,
+            FailureMode::from_name(on_failure)?,
+        );
+        network.set_step_halving(min_dt)?;
+        Ok(network)
+    }
+
+    /// Advance the network by `n` steps of `dt`.
+    #[args(n = "1")]
+    fn step(&mut self, n: usize) -> PyResult<()> {
+        self.advance(n)
+    }
+
+    /// The failure that stopped the last call with `on_failure='stop'`, as
+    /// a dict with 'step' (of the call, counted from 0), 'time',
+    /// 'node_index', 'value' and 'message', or None if it ran through.
+    #[getter(failure)]
+    fn py_failure<'py>(&self, py: Python<'py>) -> PyResult<Option<&'py PyDict>> {
+        self.failure.as_ref().map(|failure| failure.to_dict(py)).transpose()
+    }
+
+    /// Advance the network by `steps` steps of `dt`, recording the
+    /// temperatures after every `record_every` steps.
+    ///
+    /// Returns
+    /// -------
+    /// times : ndarray of shape (n_records, )
+    ///     Simulated time of each record.
+    /// temperatures : ndarray of shape (n_records, N)
+    ///     Temperatures of each node at each record.
+    /// edge_flows : ndarray of shape (n_records, E)
+    ///     Heat flow through each edge at each record [W]. Only returned
+    ///     if `edge_flows` is True.
+    #[args(record_every = "1", edge_flows = "false")]
+    fn record(&mut self, py: Python, steps: usize, record_every: usize, edge_flows: bool) -> PyResult<PyObject> {
+        let recording = self.advance_recording(steps, record_every, edge_flows)?;
+        Ok(recording.into_py(py))
+    }
+
+    /// Advance the network by `duration` [s] with adaptive time stepping
+    /// (Dormand–Prince 5(4)), keeping the local error of every node within
+    /// atol + rtol * |T|.
+    ///
+    /// Only networks whose edges and capacities stay constant during the run
+    /// can be integrated this way: constant boundary temperatures, powers and
+    /// mass flows, no controllers and no temperature-dependent properties.
+    /// Others raise a ChillValidationError, as does a negative or non-finite
+    /// duration. The energy balance and the edge flow averaging restart at the
+    /// state reached.
+    ///
+    /// Returns
+    /// -------
+    /// dict
+    ///     Statistics with keys 'accepted_steps', 'rejected_steps', 'min_dt' and 'max_dt'.
+    #[pyo3(name = "run_adaptive")]
+    #[args(atol = "1e-6", rtol = "1e-6", max_steps = "1_000_000")]
+    fn py_run_adaptive<'py>(
+        &mut self,
+        py: Python<'py>,
+        duration: f64,
+        atol: f64,
+        rtol: f64,
+        max_steps: usize,
+    ) -> PyResult<&'py PyDict> {
+        self.run_adaptive(duration, atol, rtol, max_steps)?.to_dict(py)
+    }
+
+    /// Replace the temperatures with the equilibrium of the network.
+    ///
+    /// Boundary nodes and nodes of infinite capacity keep their temperature;
+    /// the schedules are taken at the current time. Temperature-dependent
+    /// conductances and convection correlations follow the solution. A
+    /// network with controllers raises a ChillValidationError.
+    ///
+    /// Returns
+    /// -------
+    /// dict
+    ///     Convergence report with keys 'iterations' (Newton iterations) and
+    ///     'residual_norm' (net heat flow into the free nodes [W]).
+    #[pyo3(name = "solve_steady_state")]
+    #[args(tol = "1e-8", max_iter = "100")]
+    fn py_solve_steady_state<'py>(&mut self, py: Python<'py>, tol: f64, max_iter: usize) -> PyResult<&'py PyDict> {
+        self.steady_state(tol, max_iter)?.to_dict(py)
+    }
+
+    /// Largest stable time step of the explicit Euler scheme at the current
+    /// state [s], inf if no node limits it.
+    #[pyo3(name = "stable_time_step")]
+    fn py_stable_time_step(&mut self) -> PyResult<f64> {
+        self.stable_time_step()
+    }
+
+    /// Heat flow through each edge from its first to its second node at the
+    /// current temperatures [W].
+    #[getter]
+    fn edge_flows<'py>(&mut self, py: Python<'py>) -> &'py PyArray1<f64> {
+        PyArray1::from_slice(py, self.current_edge_flows())
+    }
+
+    /// Heat flow through each edge averaged over the steps taken since
+    /// construction or `reset_edge_flows` [W]. Requires `edge_flows=True`.
+    #[getter(mean_edge_flows)]
+    fn py_mean_edge_flows<'py>(&mut self, py: Python<'py>) -> PyResult<&'py PyArray1<f64>> {
+        let mean = self.mean_edge_flows().ok_or_else(|| {
+            PyValueError::new_err("Edge flows are not tracked, construct the Network with edge_flows=True")
+        })?;
+        Ok(PyArray1::from_vec(py, mean))
+    }
+
+    /// Restart averaging the heat flow through each edge.
+    fn reset_edge_flows(&mut self) {
+        self.track_edge_flows();
+    }
+
+    /// Energy bookkeeping of the free nodes since construction or
+    /// `reset_energy_balance` [J]. Requires `energy_balance=True`.
+    ///
+    /// A dict with 'heat_input' (injected by HeatInput edges), 'boundary'
+    /// (received from the boundary nodes and the nodes with infinite
+    /// capacity), 'stored' (change of sum(C * T)) and 'error' (stored -
+    /// heat_input - boundary). The energy entering in each step is integrated
+    /// with the trapezoidal rule, so the error measures the time
+    /// discretization error.
+    #[getter]
+    fn energy_balance<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        self.energy_report(py)?.ok_or_else(|| {
+            PyValueError::new_err("Energy is not tracked, construct the Network with energy_balance=True")
+        })
+    }
+
+    /// Restart the energy bookkeeping at the current state.
+    fn reset_energy_balance(&mut self) {
+        self.track_energy();
+    }
+
+    /// Current temperatures of each node.
+    #[getter]
+    fn temperatures<'py>(&self, py: Python<'py>) -> &'py PyArray1<f64> {
+        PyArray1::from_slice(py, &self.temperatures)
+    }
+
+    #[setter]
+    fn set_temperatures(&mut self, temperatures: PyReadonlyArray1<f64>) -> PyResult<()> {
+        if temperatures.len() != self.temperatures.len() {
+            return Err(invalid_value(
+                format!("Expected {} temperatures, got {}", self.temperatures.len(), temperatures.len()),
+                ErrorInfo::default(),
+            ));
+        }
+        for (node, &temperature) in temperatures.as_array().iter().enumerate() {
+            if let Some(message) = temperature_problem(temperature) {
+                return Err(invalid_value(
+                    format!("node {}: {}", node, message),
+                    ErrorInfo {
+                        node_index: Some(node),
+                        value: Some(temperature),
+                        ..ErrorInfo::default()
+                    },
+                ));
+            }
+        }
+        self.temperatures = temperatures.as_array().to_vec();
+        self.reset_energy();
+        Ok(())
+    }
+
+    /// Heat capacities of each node, at the current temperature for the
+    /// temperature-dependent ones. Boundary nodes keep theirs, unused until
+    /// they are cleared.
+    #[getter]
+    fn capacities<'py>(&self, py: Python<'py>) -> &'py PyArray1<f64> {
+        PyArray1::from_slice(py, &self.capacities)
+    }
+
+    /// Set the heat capacity of a single node, replacing its
+    /// temperature-dependent capacity if any.
+    ///
+    /// A boundary node keeps the capacity for when it is cleared.
+    fn set_capacity(&mut self, node: usize, capacity: f64) -> PyResult<()> {
+        let slot = self.capacities.get_mut(node).ok_or_else(|| node_out_of_range(node))?;
+        if let Some(message) = capacity_problem(capacity) {
+            return Err(invalid_value(
+                format!("node {}: {}", node, message),
+                ErrorInfo {
+                    node_index: Some(node),
+                    value: Some(capacity),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        *slot = capacity;
+        self.release_node(node);
+        self.fixed_nodes_changed();
+        Ok(())
+    }
+
+    /// Prescribe the temperature of a node instead of integrating it.
+    ///
+    /// Parameters
+    /// ----------
+    /// node : int
+    ///     Index of the node.
+    /// temperature : float, (ndarray, ndarray), Profile or callable
+    ///     A constant temperature; a schedule given as (times, temperatures)
+    ///     that is linearly interpolated in the simulated time and held
+    ///     constant outside the table; a `Profile` for step interpolation and
+    ///     periodic repetition; or a function of the simulated time, called
+    ///     at every step.
+    #[pyo3(name = "set_boundary")]
+    fn py_set_boundary(&mut self, node: usize, temperature: &PyAny) -> PyResult<()> {
+        self.set_boundary(node, Schedule::extract(temperature)?)
+    }
+
+    /// Let the power of a HeatInput edge follow a profile in the simulated time.
+    ///
+    /// Parameters
+    /// ----------
+    /// edge : int
+    ///     Index of the HeatInput edge.
+    /// power : float, (ndarray, ndarray), Profile or callable
+    ///     A constant power [W], a table given as (times, powers) that is
+    ///     linearly interpolated, a `Profile` for step interpolation and
+    ///     periodic repetition, or a function of the simulated time.
+    #[pyo3(name = "set_heat_input")]
+    fn py_set_heat_input(&mut self, edge: usize, power: &PyAny) -> PyResult<()> {
+        self.set_heat_input(edge, Schedule::extract(power)?)
+    }
+
+    /// Let a `Thermostat` or a `PidController` drive the power of its
+    /// HeatInput edge, replacing the power profile or controller of that
+    /// edge. The controller is evaluated at every step.
+    #[pyo3(name = "add_controller")]
+    fn py_add_controller(&mut self, controller: &PyAny) -> PyResult<()> {
+        self.add_controller(Controller::extract(controller)?)
+    }
+
+    /// State of each controller as a dict with 'type', 'edge', 'sensor' and,
+    /// for a thermostat, 'on', 'duty_cycle' (fraction of the simulated time
+    /// the heater was on), 'on_time' [s], and the time and new state of every
+    /// switching in 'switch_times' and 'switch_states'; for a PID controller,
+    /// 'setpoint', 'power' [W], 'error' and 'integral' of the last sample,
+    /// 'saturated' (the output hit a limit) and the number of 'samples'.
+    #[getter]
+    fn controllers<'py>(&self, py: Python<'py>) -> PyResult<Vec<&'py PyDict>> {
+        self.controller_reports(py)
+    }
+
+    /// Let the conductance of a Transfer edge depend on temperature.
+    ///
+    /// The resistance of the edge is replaced by 1 / (geometry * k) at every
+    /// step, evaluated at the temperatures of its nodes at the start of the step.
+    ///
+    /// Parameters
+    /// ----------
+    /// edge : int
+    ///     Index of the Transfer edge.
+    /// conductivity : (ndarray, ndarray), Profile or callable
+    ///     Thermal conductivity k [W/(m K)] as a table (temperatures,
+    ///     conductivities), linearly interpolated and held constant outside,
+    ///     as a `Profile` over temperature, or as a function of temperature.
+    /// geometry : float
+    ///     Cross-section over length of the link, A / L [m].
+    /// mode : str, optional
+    ///     'mean' (default) evaluates k at the mean temperature of the two
+    ///     nodes; 'integral' uses the conductivity integral, the heat flow
+    ///     being geometry * ∫k dT between the two node temperatures.
+    #[pyo3(name = "set_conductivity")]
+    #[args(mode = "\"mean\"")]
+    fn py_set_conductivity(&mut self, edge: usize, conductivity: &PyAny, geometry: f64, mode: &str) -> PyResult<()> {
+        let conductivity = Conductivity::new(
+            Schedule::extract(conductivity)?,
+            geometry,
+            ConductivityMode::from_name(mode)?,
+        )?;
+        self.set_conductivity(edge, conductivity)
+    }
+
+    /// Set the heat transfer coefficient of a Convection edge.
+    ///
+    /// A Convection edge carries q = parameter * |ΔT|^n * ΔT with n = 0.25
+    /// unless a power law sets another exponent; its parameter is the
+    /// coefficient times the area. The correlations replace the parameter by
+    /// h * A at every step, with the fluid properties at the film temperature.
+    ///
+    /// Parameters
+    /// ----------
+    /// edge : int
+    ///     Index of the Convection edge.
+    /// convection : Convection
+    ///     The power law or correlation, see `Convection`.
+    #[pyo3(name = "set_convection")]
+    fn py_set_convection(&mut self, edge: usize, convection: Convection) -> PyResult<()> {
+        self.set_convection(edge, convection)
+    }
+
+    /// Let the mass flow of an Advection edge follow a profile in the simulated time.
+    ///
+    /// An Advection edge carries the heat of a fluid flowing from its first
+    /// node to its second, ṁ * cp * (T1 - T2) into the second node; its
+    /// parameter is the capacity rate ṁ * cp [W/K]. The first node is not
+    /// cooled by the edge: the fluid leaving it is accounted for by the edge
+    /// that feeds it, so a pumped loop is a closed chain of Advection edges.
+    ///
+    /// Parameters
+    /// ----------
+    /// edge : int
+    ///     Index of the Advection edge.
+    /// mass_flow : float, (ndarray, ndarray), Profile or callable
+    ///     A constant mass flow [kg/s], a table given as (times, flows) that
+    ///     is linearly interpolated, a `Profile`, or a function of the
+    ///     simulated time. It must not be negative.
+    /// specific_heat : float
+    ///     Specific heat capacity cp of the fluid [J/(kg K)].
+    #[pyo3(name = "set_mass_flow")]
+    fn py_set_mass_flow(&mut self, edge: usize, mass_flow: &PyAny, specific_heat: f64) -> PyResult<()> {
+        self.set_mass_flow(edge, Schedule::extract(mass_flow)?, specific_heat)
+    }
+
+    /// Let the heat capacity of a node depend on its temperature.
+    ///
+    /// The node is stepped in enthalpy, so the energy it exchanges is
+    /// conserved however steeply the capacity varies: each step delivers
+    /// C * ΔT with the capacity at the start of the step, and the node moves
+    /// along ∫C dT by that energy.
+    ///
+    /// A function is called from every step: ∫C dT is averaged with a
+    /// 16-interval Simpson rule and inverted by Newton iterations, which
+    /// costs some tens of Python calls per node and step. A table costs
+    /// none and is preferable for long runs.
+    ///
+    /// Parameters
+    /// ----------
+    /// node : int
+    ///     Index of a free node.
+    /// capacity : (ndarray, ndarray), Profile or callable
+    ///     Heat capacity C [J/K] as a table (temperatures, capacities),
+    ///     linearly interpolated and held constant outside, as a `Profile`
+    ///     over temperature, or as a function of temperature.
+    #[pyo3(name = "set_heat_capacity")]
+    fn py_set_heat_capacity(&mut self, node: usize, capacity: &PyAny) -> PyResult<()> {
+        self.set_heat_capacity(node, HeatCapacity::new(Schedule::extract(capacity)?)?)
+    }
+
+    /// Give a node a latent heat of melting.
+    ///
+    /// The latent heat is absorbed evenly across a mushy zone of `width`
+    /// centred on `melt_temperature`, on top of the sensible heat capacity
+    /// of the node (its temperature-dependent one if set, otherwise its
+    /// constant capacity). The node is stepped in enthalpy like any node with
+    /// a temperature-dependent capacity, see `set_heat_capacity`.
+    ///
+    /// Parameters
+    /// ----------
+    /// node : int
+    ///     Index of a free node.
+    /// melt_temperature : float
+    ///     Melting temperature, the centre of the mushy zone [K].
+    /// latent_heat : float
+    ///     Latent heat of the whole node [J].
+    /// width : float
+    ///     Width of the mushy zone [K].
+    #[pyo3(name = "set_phase_change")]
+    fn py_set_phase_change(&mut self, node: usize, melt_temperature: f64, latent_heat: f64, width: f64) -> PyResult<()> {
+        self.set_phase_change(node, PhaseChange::new(melt_temperature, latent_heat, width)?)
+    }
+
+    /// Liquid fraction of each node, from 0 (solid) to 1 (liquid) across the
+    /// mushy zone; NaN for the nodes without a phase change.
+    #[getter(melt_fractions)]
+    fn py_melt_fractions<'py>(&self, py: Python<'py>) -> &'py PyArray1<f64> {
+        PyArray1::from_vec(py, self.melt_fractions())
+    }
+
+    /// Integrate a boundary node again with the capacity it had before.
+    #[pyo3(name = "clear_boundary")]
+    fn py_clear_boundary(&mut self, node: usize) -> PyResult<()> {
+        self.clear_boundary(node)
+    }
+
+    /// Indices of the boundary nodes.
+    #[getter]
+    fn boundary_nodes(&self) -> Vec<usize> {
+        self.boundaries.iter().map(|b| b.node).collect()
+    }
+
+    /// Parameters of each edge. Setting them replaces the power profiles,
+    /// controllers, conductivities and convection correlations.
+    #[getter]
+    fn parameters<'py>(&self, py: Python<'py>) -> &'py PyArray1<f64> {
+        let parameters: Vec<f64> = self.edges.iter().map(|edge| edge.parameter).collect();
+        PyArray1::from_vec(py, parameters)
+    }
+
+    #[setter]
+    fn set_parameters(&mut self, parameters: PyReadonlyArray1<f64>) -> PyResult<()> {
+        if parameters.len() != self.edges.len() {
+            return Err(invalid_value(
+                format!("Expected {} parameters, got {}", self.edges.len(), parameters.len()),
+                ErrorInfo::default(),
+            ));
+        }
+        for (index, (edge, &parameter)) in self.edges.iter().zip(parameters.as_array().iter()).enumerate() {
+            if let Some(message) = parameter_problem(edge.edge_type, parameter) {
+                return Err(invalid_parameter(index, parameter, message));
+            }
+        }
+        for (edge, &parameter) in self.edges.iter_mut().zip(parameters.as_array().iter()) {
+            edge.parameter = parameter;
+            restore_exponent(edge);
+        }
+        self.heat_inputs.clear();
+        self.controllers.clear();
+        self.conductivities.clear();
+        self.convections.clear();
+        self.mass_flows.clear();
+        self.refresh_energy();
+        Ok(())
+    }
+
+    /// Set the parameter of a single edge, replacing its power profile,
+    /// controller, conductivity, convection correlation or mass flow if any.
+    fn set_parameter(&mut self, edge: usize, parameter: f64) -> PyResult<()> {
+        let index = edge;
+        let edge = self.edges.get_mut(index).ok_or_else(|| edge_out_of_range(index))?;
+        if let Some(message) = parameter_problem(edge.edge_type, parameter) {
+            return Err(invalid_parameter(index, parameter, message));
+        }
+        edge.parameter = parameter;
+        self.release_edge(index);
+        self.refresh_energy();
+        Ok(())
+    }
+
+    /// Number of times a step was halved since construction.
+    #[getter(halvings)]
+    fn py_halvings(&self) -> usize {
+        self.halvings
+    }
+
+    /// Time step for the simulation.
+    #[getter]
+    fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    #[setter]
+    fn set_dt(&mut self, dt: f64) -> PyResult<()> {
+        check_value(time_step_problem(dt), dt)?;
+        self.dt = dt;
+        Ok(())
+    }
+
+    /// Simulated time since construction.
+    #[getter]
+    fn time(&self) -> f64 {
+        self.time
+    }
+
+    #[setter]
+    fn set_time(&mut self, time: f64) {
+        self.time = time;
+    }
+
+    /// Name of the time integration scheme.
+    #[getter]
+    fn method(&self) -> &'static str {
+        self.method.name()
+    }
+}
@@ -0,0 +1,307 @@
+// The pyo3 0.16 #[pymethods] expansion trips this rustc lint.
+#![allow(non_local_definitions)]
+
+use pyo3::prelude::*;
+
+use crate::errors::{invalid_value, ErrorInfo};
+
+/// How a profile is evaluated between its samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    Linear,
+    /// Hold the value of the last sample.
+    Step,
+}
+
+impl Interpolation {
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "linear" => Ok(Interpolation::Linear),
+            "step" => Ok(Interpolation::Step),
+            _ => Err(invalid_value(
+                format!("Unknown interpolation '{}', expected 'linear' or 'step'", name),
+                ErrorInfo::default(),
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Interpolation::Linear => "linear",
+            Interpolation::Step => "step",
+        }
+    }
+}
+
+/// A quantity tabulated against the simulated time.
+///
+/// Parameters
+/// ----------
+/// times : ndarray of shape (K, )
+///     Strictly increasing sample times [s].
+/// values : ndarray of shape (K, )
+///     Value at each sample time.
+/// interpolation : str, optional
+///     'linear' (default) or 'step', which holds the value of the last sample.
+/// period : float, optional
+///     Repeat the table with this period, counted from the first sample
+///     time. It must cover the table; the gap between the last sample and
+///     the next period is bridged like any other interval. Default is None,
+///     which holds the first and last values outside the table.
+#[pyclass(module = "chill")]
+#[derive(Debug, Clone)]
+pub struct Profile {
+    times: Vec<f64>,
+    values: Vec<f64>,
+    interpolation: Interpolation,
+    period: Option<f64>,
+}
+
+impl Profile {
+    pub fn constant(value: f64) -> Self {
+        Profile {
+            times: vec![0.0],
+            values: vec![value],
+            interpolation: Interpolation::Step,
+            period: None,
+        }
+    }
+
+    pub fn build(
+        times: Vec<f64>,
+        values: Vec<f64>,
+        interpolation: Interpolation,
+        period: Option<f64>,
+    ) -> PyResult<Self> {
+        if times.is_empty() || times.len() != values.len() {
+            return Err(invalid_value(
+                format!(
+                    "A profile needs matching, non-empty times and values, got {} times and {} values",
+                    times.len(),
+                    values.len()
+                ),
+                ErrorInfo::default(),
+            ));
+        }
+        if let Some(&x) = times.iter().chain(&values).find(|x| !x.is_finite()) {
+            return Err(invalid_value(
+                "Profile times and values must be finite".to_string(),
+                ErrorInfo {
+                    value: Some(x),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        if times.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(invalid_value(
+                "Profile times must be strictly increasing".to_string(),
+                ErrorInfo::default(),
+            ));
+        }
+        if let Some(period) = period {
+            let span = times[times.len() - 1] - times[0];
+            // the last sample must come before the first one repeats
+            if !period.is_finite() || period <= span {
+                return Err(invalid_value(
+                    format!("Profile period must exceed the span of the table ({}), got {}", span, period),
+                    ErrorInfo {
+                        value: Some(period),
+                        ..ErrorInfo::default()
+                    },
+                ));
+            }
+        }
+        Ok(Profile {
+            times,
+            values,
+            interpolation,
+            period,
+        })
+    }
+
+    /// A constant given as a float, a table given as (times, values) or a `Profile`.
+    pub fn extract(value: &PyAny) -> PyResult<Self> {
+        if let Ok(profile) = value.extract::<PyRef<Profile>>() {
+            return Ok(profile.clone());
+        }
+        if let Ok(constant) = value.extract::<f64>() {
+            if !constant.is_finite() {
+                return Err(invalid_value(
+                    format!("Profile value is {}", constant),
+                    ErrorInfo {
+                        value: Some(constant),
+                        ..ErrorInfo::default()
+                    },
+                ));
+            }
+            return Ok(Profile::constant(constant));
+        }
+        let (times, values): (Vec<f64>, Vec<f64>) = value.extract()?;
+        Profile::build(times, values, Interpolation::Linear, None)
+    }
+
+    pub fn value_at(&self, time: f64) -> f64 {
+        let first = self.times[0];
+        let last = self.times.len() - 1;
+        let time = match self.period {
+            Some(period) => first + (time - first).rem_euclid(period),
+            None => time,
+        };
+        if time <= first {
+            return self.values[0];
+        }
+        if time >= self.times[last] {
+            return match (self.period, self.interpolation) {
+                // bridge to the first sample of the next period
+                (Some(period), Interpolation::Linear) => {
+                    let fraction = (time - self.times[last]) / (first + period - self.times[last]);
+                    self.values[last] + (self.values[0] - self.values[last]) * fraction
+                }
+                _ => self.values[last],
+            };
+        }
+        // first sample after `time`
+        let i = self.times.partition_point(|&t| t <= time);
+        let (t0, t1) = (self.times[i - 1], self.times[i]);
+        let (v0, v1) = (self.values[i - 1], self.values[i]);
+        match self.interpolation {
+            Interpolation::Linear => v0 + (v1 - v0) * (time - t0) / (t1 - t0),
+            Interpolation::Step => v0,
+        }
+    }
+
+    pub fn is_periodic(&self) -> bool {
+        self.period.is_some()
+    }
+
+    /// True if the profile takes the same value at all times.
+    pub fn is_constant(&self) -> bool {
+        self.values.iter().all(|&v| v == self.values[0])
+    }
+
+    /// Exact integral of the profile from `a` to `b`, for a profile without period.
+    pub fn integral(&self, a: f64, b: f64) -> f64 {
+        if b < a {
+            return -self.integral(b, a);
+        }
+        // the profile is linear (or constant) between consecutive breakpoints
+        let mut total = 0.0;
+        let mut left = a;
+        for &sample in self.times.iter().filter(|&&t| t > a && t < b).chain(std::iter::once(&b)) {
+            total += match self.interpolation {
+                Interpolation::Linear => 0.5 * (self.value_at(left) + self.value_at(sample)) * (sample - left),
+                Interpolation::Step => self.value_at(left) * (sample - left),
+            };
+            left = sample;
+        }
+        total
+    }
+}
+
+#[pymethods]
+impl Profile {
+    #[new]
+    #[args(interpolation = "\"linear\"", period = "None")]
+    fn new(times: Vec<f64>, values: Vec<f64>, interpolation: &str, period: Option<f64>) -> PyResult<Self> {
+        Profile::build(times, values, Interpolation::from_name(interpolation)?, period)
+    }
+
+    /// Evaluate the profile at `time`.
+    fn __call__(&self, time: f64) -> f64 {
+        self.value_at(time)
+    }
+
+    #[getter]
+    fn times(&self) -> Vec<f64> {
+        self.times.clone()
+    }
+
+    #[getter]
+    fn values(&self) -> Vec<f64> {
+        self.values.clone()
+    }
+
+    #[getter]
+    fn interpolation(&self) -> &'static str {
+        self.interpolation.name()
+    }
+
+    #[getter]
+    fn period(&self) -> Option<f64> {
+        self.period
+    }
+}
+
+/// Intervals of the Simpson rule averaging a Python function.
+const SIMPSON_INTERVALS: usize = 16;
+
+/// A prescribed quantity: a tabulated profile or a Python function of time.
+///
+/// Material properties use the same tables and functions with the
+/// temperature in place of the time.
+#[derive(Clone)]
+pub enum Schedule {
+    Table(Profile),
+    /// Called with the simulated time at every step.
+    Function(PyObject),
+}
+
+impl Schedule {
+    /// A float, a (times, values) table, a `Profile` or a callable taking the time.
+    pub fn extract(value: &PyAny) -> PyResult<Self> {
+        if value.extract::<PyRef<Profile>>().is_err() && value.is_callable() {
+            return Ok(Schedule::Function(value.into()));
+        }
+        Profile::extract(value).map(Schedule::Table)
+    }
+
+    pub fn value_at(&self, time: f64) -> PyResult<f64> {
+        match self {
+            Schedule::Table(profile) => Ok(profile.value_at(time)),
+            Schedule::Function(function) => {
+                let value: f64 = Python::with_gil(|py| function.call1(py, (time,))?.extract(py))?;
+                if !value.is_finite() {
+                    return Err(invalid_value(
+                        format!("Schedule function returned {} at t = {}", value, time),
+                        ErrorInfo {
+                            value: Some(value),
+                            time: Some(time),
+                            ..ErrorInfo::default()
+                        },
+                    ));
+                }
+                Ok(value)
+            }
+        }
+    }
+
+    /// True for a table with a single value. Functions are never constant.
+    pub fn is_constant(&self) -> bool {
+        match self {
+            Schedule::Table(profile) => profile.is_constant(),
+            Schedule::Function(_) => false,
+        }
+    }
+
+    /// Mean value over [a, b], the value at `a` if the interval is empty.
+    ///
+    /// Exact for tables (without period); functions are integrated with the
+    /// Simpson rule.
+    pub fn mean(&self, a: f64, b: f64) -> PyResult<f64> {
+        if (b - a).abs() <= 1e-12 * a.abs().max(b.abs()).max(1.0) {
+            return self.value_at(0.5 * (a + b));
+        }
+        match self {
+            Schedule::Table(profile) => Ok(profile.integral(a, b) / (b - a)),
+            Schedule::Function(_) => {
+                let h = (b - a) / SIMPSON_INTERVALS as f64;
+                let mut total = self.value_at(a)? + self.value_at(b)?;
+                for i in 1..SIMPSON_INTERVALS {
+                    let weight = if i % 2 == 1 { 4.0 } else { 2.0 };
+                    total += weight * self.value_at(a + i as f64 * h)?;
+                }
+                Ok(total / (3.0 * SIMPSON_INTERVALS as f64))
+            }
+        }
+    }
+}
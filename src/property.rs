@@ -0,0 +1,267 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::errors::{invalid_value, ErrorInfo};
+use crate::profile::Schedule;
+
+/// How the conductivity of an edge is averaged over its end temperatures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConductivityMode {
+    /// k at the mean temperature of the two nodes.
+    Mean,
+    /// The conductivity integral, ∫k dT / ΔT between the two nodes.
+    Integral,
+}
+
+impl ConductivityMode {
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "mean" => Ok(ConductivityMode::Mean),
+            "integral" => Ok(ConductivityMode::Integral),
+            _ => Err(invalid_value(
+                format!("Unknown conductivity mode '{}', expected 'mean' or 'integral'", name),
+                ErrorInfo::default(),
+            )),
+        }
+    }
+}
+
+/// Temperature-dependent conductance of a Transfer edge, G = geometry * k(T).
+pub struct Conductivity {
+    /// k(T) [W/(m K)] as a table or function of the temperature.
+    conductivity: Schedule,
+    /// Cross-section over length, A / L [m].
+    geometry: f64,
+    mode: ConductivityMode,
+}
+
+impl Conductivity {
+    pub fn new(conductivity: Schedule, geometry: f64, mode: ConductivityMode) -> PyResult<Self> {
+        if !geometry.is_finite() || geometry <= 0.0 {
+            return Err(invalid_value(
+                format!("Conductance geometry (A / L) must be positive, got {}", geometry),
+                ErrorInfo {
+                    value: Some(geometry),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        if let Schedule::Table(profile) = &conductivity {
+            if profile.is_periodic() {
+                return Err(invalid_value(
+                    "A conductivity table cannot be periodic".to_string(),
+                    ErrorInfo::default(),
+                ));
+            }
+        }
+        Ok(Conductivity {
+            conductivity,
+            geometry,
+            mode,
+        })
+    }
+
+    /// A (conductivity, geometry) or (conductivity, geometry, mode) tuple.
+    pub fn extract(value: &PyAny) -> PyResult<Self> {
+        let (conductivity, geometry, mode): (&PyAny, f64, &str) = match value.extract::<(&PyAny, f64)>() {
+            Ok((conductivity, geometry)) => (conductivity, geometry, "mean"),
+            Err(_) => value.extract()?,
+        };
+        Conductivity::new(Schedule::extract(conductivity)?, geometry, ConductivityMode::from_name(mode)?)
+    }
+
+    /// Heat resistance of the edge between nodes at `t1` and `t2` [K/W].
+    pub fn resistance(&self, t1: f64, t2: f64) -> PyResult<f64> {
+        let k = match self.mode {
+            ConductivityMode::Mean => self.conductivity.value_at(0.5 * (t1 + t2))?,
+            ConductivityMode::Integral => self.conductivity.mean(t2, t1)?,
+        };
+        if k.is_nan() || k <= 0.0 {
+            return Err(invalid_value(
+                format!("Conductivity must be positive, got {} between {} K and {} K", k, t1, t2),
+                ErrorInfo {
+                    value: Some(k),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        Ok(1.0 / (self.geometry * k))
+    }
+}
+
+/// Latent heat absorbed on melting, spread evenly over a mushy zone around
+/// the melting temperature.
+#[derive(Debug, Clone, Copy)]
+pub struct PhaseChange {
+    /// Centre of the mushy zone [K].
+    pub melt_temperature: f64,
+    /// Latent heat of the whole node [J].
+    pub latent_heat: f64,
+    /// Width of the mushy zone [K].
+    pub width: f64,
+}
+
+impl PhaseChange {
+    pub fn new(melt_temperature: f64, latent_heat: f64, width: f64) -> PyResult<Self> {
+        if !melt_temperature.is_finite() {
+            return Err(invalid_value(
+                format!("Melting temperature must be finite, got {}", melt_temperature),
+                ErrorInfo {
+                    value: Some(melt_temperature),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        if !latent_heat.is_finite() || latent_heat < 0.0 {
+            return Err(invalid_value(
+                format!("Latent heat must be finite and not negative, got {}", latent_heat),
+                ErrorInfo {
+                    value: Some(latent_heat),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        if !width.is_finite() || width <= 0.0 {
+            return Err(invalid_value(
+                format!("Mushy zone width must be positive, got {}", width),
+                ErrorInfo {
+                    value: Some(width),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        Ok(PhaseChange {
+            melt_temperature,
+            latent_heat,
+            width,
+        })
+    }
+
+    /// Liquid fraction at `temperature`, linear across the mushy zone.
+    pub fn melt_fraction(&self, temperature: f64) -> f64 {
+        ((temperature - self.melt_temperature) / self.width + 0.5).clamp(0.0, 1.0)
+    }
+
+    /// Latent part of the apparent heat capacity [J/K].
+    fn capacity(&self, temperature: f64) -> f64 {
+        if (temperature - self.melt_temperature).abs() < 0.5 * self.width {
+            self.latent_heat / self.width
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Largest number of iterations when inverting the enthalpy.
+const MAX_ENTHALPY_ITERATIONS: usize = 100;
+
+/// Temperature-dependent heat capacity C(T) of a node.
+///
+/// The node is stepped in enthalpy: the energy a step delivers with the
+/// capacity at the start of the step is turned into the temperature at which
+/// ∫C dT from the start of the step equals that energy. The energy exchanged
+/// between nodes is therefore conserved however steeply C varies, and latent
+/// heat enters as a steep C over the mushy zone.
+pub struct HeatCapacity {
+    /// C(T) [J/K] as a table or function of the temperature.
+    capacity: Schedule,
+    phase_change: Option<PhaseChange>,
+}
+
+impl HeatCapacity {
+    pub fn new(capacity: Schedule) -> PyResult<Self> {
+        if let Schedule::Table(profile) = &capacity {
+            if profile.is_periodic() {
+                return Err(invalid_value(
+                    "A heat capacity table cannot be periodic".to_string(),
+                    ErrorInfo::default(),
+                ));
+            }
+        }
+        Ok(HeatCapacity {
+            capacity,
+            phase_change: None,
+        })
+    }
+
+    /// Add the latent heat of `phase_change` to the sensible capacity.
+    pub fn with_phase_change(self, phase_change: PhaseChange) -> Self {
+        HeatCapacity {
+            phase_change: Some(phase_change),
+            ..self
+        }
+    }
+
+    /// Liquid fraction at `temperature`, None without a phase change.
+    pub fn melt_fraction(&self, temperature: f64) -> Option<f64> {
+        self.phase_change.map(|phase_change| phase_change.melt_fraction(temperature))
+    }
+
+    /// Apparent heat capacity at `temperature` [J/K].
+    pub fn value_at(&self, temperature: f64) -> PyResult<f64> {
+        let latent = self.phase_change.map_or(0.0, |phase_change| phase_change.capacity(temperature));
+        let capacity = self.capacity.value_at(temperature)? + latent;
+        if capacity.is_nan() || capacity <= 0.0 {
+            return Err(invalid_value(
+                format!("Heat capacity must be positive, got {} at {} K", capacity, temperature),
+                ErrorInfo {
+                    value: Some(capacity),
+                    ..ErrorInfo::default()
+                },
+            ));
+        }
+        Ok(capacity)
+    }
+
+    /// Energy needed to go from `t0` to `t`, ∫C dT [J].
+    fn enthalpy_change(&self, t0: f64, t: f64) -> PyResult<f64> {
+        let latent = self.phase_change.map_or(0.0, |phase_change| {
+            phase_change.latent_heat * (phase_change.melt_fraction(t) - phase_change.melt_fraction(t0))
+        });
+        Ok(self.capacity.mean(t0, t)? * (t - t0) + latent)
+    }
+
+    /// Temperature reached from `t0` when `energy` [J] enters the node.
+    pub fn temperature_after(&self, t0: f64, energy: f64) -> PyResult<f64> {
+        if energy == 0.0 || !energy.is_finite() {
+            return Ok(t0 + energy);
+        }
+        let residual = |t: f64| -> PyResult<f64> { Ok(self.enthalpy_change(t0, t)? - energy) };
+        // bracket the root, the residual grows with t
+        let guess = t0 + energy / self.value_at(t0)?;
+        let (mut near, mut far) = (t0, guess);
+        let mut expansions = 0;
+        while residual(far)? * energy < 0.0 {
+            expansions += 1;
+            if expansions > MAX_ENTHALPY_ITERATIONS {
+                return Err(PyValueError::new_err(format!(
+                    "No temperature found for {} J entering a node at {} K",
+                    energy, t0
+                )));
+            }
+            near = far;
+            far = t0 + 2.0 * (far - t0);
+        }
+        let (mut lo, mut hi) = if energy > 0.0 { (near, far) } else { (far, near) };
+        // Newton, falling back to bisection when it leaves the bracket
+        let tolerance = 1e-12 * energy.abs();
+        let mut t = guess.clamp(lo, hi);
+        for _ in 0..MAX_ENTHALPY_ITERATIONS {
+            let r = residual(t)?;
+            if r.abs() <= tolerance {
+                break;
+            }
+            if r < 0.0 {
+                lo = t;
+            } else {
+                hi = t;
+            }
+            let newton = t - r / self.value_at(t)?;
+            t = if newton > lo && newton < hi { newton } else { 0.5 * (lo + hi) };
+            if hi - lo <= 1e-12 * t.abs().max(1.0) {
+                break;
+            }
+        }
+        Ok(t)
+    }
+}
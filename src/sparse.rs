@@ -0,0 +1,193 @@
+/// Square sparse matrix in compressed sparse row (CSR) layout.
+///
+/// The sparsity pattern is fixed at construction so that the values can be
+/// reassembled every step without reallocating.
+#[derive(Debug, Clone)]
+pub struct SparseMatrix {
+    n: usize,
+    row_ptr: Vec<usize>,
+    col_idx: Vec<usize>,
+    pub values: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SolveError {
+    /// The iteration did not reach the tolerance within the iteration limit.
+    NotConverged { iterations: usize, residual: f64 },
+    /// The iteration broke down (e.g. singular matrix).
+    Breakdown { iterations: usize },
+}
+
+impl std::fmt::Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::NotConverged { iterations, residual } => write!(
+                f,
+                "Linear solver did not converge after {} iterations (relative residual = {:e})",
+                iterations, residual
+            ),
+            SolveError::Breakdown { iterations } => write!(
+                f,
+                "Linear solver broke down after {} iterations (singular system?)",
+                iterations
+            ),
+        }
+    }
+}
+
+impl SparseMatrix {
+    /// Build an all-zero matrix with the given (row, column) entries.
+    /// Duplicate entries are merged.
+    pub fn from_pattern(n: usize, entries: &[(usize, usize)]) -> Self {
+        let mut sorted = entries.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+
+        let mut row_ptr = vec![0; n + 1];
+        for &(row, _) in &sorted {
+            row_ptr[row + 1] += 1;
+        }
+        for i in 0..n {
+            row_ptr[i + 1] += row_ptr[i];
+        }
+        let col_idx: Vec<usize> = sorted.iter().map(|&(_, col)| col).collect();
+        let values = vec![0.0; col_idx.len()];
+
+        SparseMatrix {
+            n,
+            row_ptr,
+            col_idx,
+            values,
+        }
+    }
+
+    /// Position of the entry (row, col) in `values`, if it is part of the pattern.
+    pub fn position(&self, row: usize, col: usize) -> Option<usize> {
+        let start = self.row_ptr[row];
+        let end = self.row_ptr[row + 1];
+        self.col_idx[start..end]
+            .binary_search(&col)
+            .ok()
+            .map(|offset| start + offset)
+    }
+
+    pub fn clear(&mut self) {
+        self.values.iter_mut().for_each(|v| *v = 0.0);
+    }
+
+    /// y = A x
+    pub fn mul_vec(&self, x: &[f64], y: &mut [f64]) {
+        for (row, y_row) in y.iter_mut().enumerate().take(self.n) {
+            let start = self.row_ptr[row];
+            let end = self.row_ptr[row + 1];
+            *y_row = self.col_idx[start..end]
+                .iter()
+                .zip(&self.values[start..end])
+                .map(|(&col, &value)| value * x[col])
+                .sum();
+        }
+    }
+
+    pub fn diagonal(&self) -> Vec<f64> {
+        (0..self.n)
+            .map(|i| self.position(i, i).map_or(0.0, |p| self.values[p]))
+            .collect()
+    }
+
+    /// Solve A x = b with Jacobi-preconditioned BiCGSTAB.
+    ///
+    /// `x` holds the initial guess on entry and the solution on exit.
+    /// Returns the number of iterations used.
+    pub fn solve(&self, b: &[f64], x: &mut [f64], tol: f64, max_iter: usize) -> Result<usize, SolveError> {
+        let n = self.n;
+        let inv_diag: Vec<f64> = self
+            .diagonal()
+            .iter()
+            .map(|&d| if d != 0.0 { 1.0 / d } else { 1.0 })
+            .collect();
+
+        let b_norm = norm(b);
+        if b_norm == 0.0 {
+            x.iter_mut().for_each(|v| *v = 0.0);
+            return Ok(0);
+        }
+
+        let mut r = vec![0.0; n];
+        self.mul_vec(x, &mut r);
+        for i in 0..n {
+            r[i] = b[i] - r[i];
+        }
+        if norm(&r) <= tol * b_norm {
+            return Ok(0);
+        }
+        let r_hat = r.clone();
+
+        let mut p = vec![0.0; n];
+        let mut v = vec![0.0; n];
+        let mut y = vec![0.0; n];
+        let mut z = vec![0.0; n];
+        let mut s = vec![0.0; n];
+        let mut t = vec![0.0; n];
+        let (mut rho, mut alpha, mut omega) = (1.0, 1.0, 1.0);
+
+        for iteration in 1..=max_iter {
+            let rho_new = dot(&r_hat, &r);
+            if rho_new == 0.0 || omega == 0.0 {
+                return Err(SolveError::Breakdown { iterations: iteration });
+            }
+            let beta = (rho_new / rho) * (alpha / omega);
+            for i in 0..n {
+                p[i] = r[i] + beta * (p[i] - omega * v[i]);
+                y[i] = inv_diag[i] * p[i];
+            }
+            self.mul_vec(&y, &mut v);
+            let r_hat_v = dot(&r_hat, &v);
+            if r_hat_v == 0.0 {
+                return Err(SolveError::Breakdown { iterations: iteration });
+            }
+            alpha = rho_new / r_hat_v;
+            for i in 0..n {
+                s[i] = r[i] - alpha * v[i];
+            }
+            if norm(&s) <= tol * b_norm {
+                for i in 0..n {
+                    x[i] += alpha * y[i];
+                }
+                return Ok(iteration);
+            }
+            for i in 0..n {
+                z[i] = inv_diag[i] * s[i];
+            }
+            self.mul_vec(&z, &mut t);
+            let t_t = dot(&t, &t);
+            omega = if t_t != 0.0 { dot(&t, &s) / t_t } else { 0.0 };
+            for i in 0..n {
+                x[i] += alpha * y[i] + omega * z[i];
+                r[i] = s[i] - omega * t[i];
+            }
+            let residual = norm(&r) / b_norm;
+            if residual <= tol {
+                return Ok(iteration);
+            }
+            if iteration == max_iter {
+                return Err(SolveError::NotConverged {
+                    iterations: iteration,
+                    residual,
+                });
+            }
+            rho = rho_new;
+        }
+        Err(SolveError::NotConverged {
+            iterations: max_iter,
+            residual: norm(&r) / b_norm,
+        })
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn norm(a: &[f64]) -> f64 {
+    dot(a, a).sqrt()
+}
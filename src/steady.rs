@@ -0,0 +1,131 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::edge::{heat_balance, Edge};
+use crate::implicit::ThetaStepper;
+use crate::sparse::SolveError;
+
+/// Smallest fraction of the Newton step tried by the backtracking line search.
+const MIN_STEP_FRACTION: f64 = 1.0 / 1024.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SteadyStateReport {
+    pub iterations: usize,
+    pub residual_norm: f64,
+}
+
+impl SteadyStateReport {
+    /// The report as a dict with keys 'iterations' and 'residual_norm'.
+    pub fn to_dict<'py>(self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let dict = PyDict::new(py);
+        dict.set_item("iterations", self.iterations)?;
+        dict.set_item("residual_norm", self.residual_norm)?;
+        Ok(dict)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SteadyStateError {
+    /// No node is fixed, so the equilibrium is not unique.
+    NoBoundary,
+    /// The linear solve of a Newton iteration failed.
+    Solver(SolveError),
+    /// The residual did not drop below the tolerance.
+    NotConverged { iterations: usize, residual_norm: f64 },
+}
+
+impl std::fmt::Display for SteadyStateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SteadyStateError::NoBoundary => write!(
+                f,
+                "Steady state requires at least one fixed node (a boundary or infinite capacity)"
+            ),
+            SteadyStateError::Solver(e) => write!(f, "{}", e),
+            SteadyStateError::NotConverged {
+                iterations,
+                residual_norm,
+            } => write!(
+                f,
+                "Steady state did not converge after {} iterations (residual norm = {:e} W)",
+                iterations, residual_norm
+            ),
+        }
+    }
+}
+
+/// Euclidean norm of the net heat flow into the free nodes [W].
+pub fn free_norm(flows: &[f64], fixed: &[bool]) -> f64 {
+    flows
+        .iter()
+        .zip(fixed)
+        .filter(|(_, &fixed)| !fixed)
+        .map(|(f, _)| f * f)
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Newton-solve the heat balance F(T) = 0 of the free nodes.
+///
+/// Fixed nodes keep their given temperature and act as boundaries.
+/// `temperatures` holds the initial guess on entry and the equilibrium on
+/// exit.
+///
+/// # Arguments
+///
+/// * `tol` - Residual norm [W] at which the iteration stops.
+/// * `max_iter` - Maximum number of Newton iterations.
+pub fn solve_steady_state(
+    edges: &[Edge],
+    capacities: &[f64],
+    fixed: &[bool],
+    temperatures: &mut [f64],
+    tol: f64,
+    max_iter: usize,
+) -> Result<SteadyStateReport, SteadyStateError> {
+    let has_free = fixed.iter().any(|&f| !f);
+    let has_fixed = fixed.iter().any(|&f| f);
+    if has_free && !has_fixed {
+        return Err(SteadyStateError::NoBoundary);
+    }
+
+    let mut stepper = ThetaStepper::new(fixed, edges, 1.0);
+    let mut trial = temperatures.to_vec();
+    let mut flows = vec![0.0; temperatures.len()];
+
+    let mut iteration = 0;
+    loop {
+        // dt = inf drops the capacity term, leaving the Newton system -J dT = F
+        stepper.assemble(edges, capacities, temperatures, f64::INFINITY);
+        let residual_norm = stepper.residual_norm();
+        if residual_norm <= tol {
+            return Ok(SteadyStateReport {
+                iterations: iteration,
+                residual_norm,
+            });
+        }
+        if iteration == max_iter {
+            return Err(SteadyStateError::NotConverged {
+                iterations: iteration,
+                residual_norm,
+            });
+        }
+        stepper.solve().map_err(SteadyStateError::Solver)?;
+
+        // Backtrack while the full Newton step increases the residual,
+        // which keeps the T^4 terms from overshooting.
+        let mut fraction = 1.0;
+        loop {
+            for ((t, &t0), d) in trial.iter_mut().zip(temperatures.iter()).zip(stepper.delta()) {
+                *t = t0 + fraction * d;
+            }
+            heat_balance(edges, &trial, &mut flows);
+            if free_norm(&flows, fixed) < residual_norm || fraction <= MIN_STEP_FRACTION {
+                break;
+            }
+            fraction *= 0.5;
+        }
+        temperatures.copy_from_slice(&trial);
+        iteration += 1;
+    }
+}
@@ -0,0 +1,254 @@
+use numpy::ndarray::{ArrayView1, ArrayView2};
+use pyo3::prelude::*;
+
+use crate::edge::{Edge, EdgeType};
+use crate::errors::validation_error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProblemKind {
+    /// The arrays disagree in shape.
+    Shape,
+    /// An edge refers to a node that does not exist.
+    NodeIndex,
+    /// An edge type code is undefined.
+    EdgeType,
+    /// A temperature, capacity or parameter has an invalid value.
+    Value,
+}
+
+/// A single problem found in the network arrays.
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub kind: ProblemKind,
+    pub edge: Option<usize>,
+    pub node: Option<usize>,
+    pub value: Option<f64>,
+    pub message: String,
+}
+
+impl Problem {
+    fn shape(message: String) -> Self {
+        Problem {
+            kind: ProblemKind::Shape,
+            edge: None,
+            node: None,
+            value: None,
+            message,
+        }
+    }
+
+    fn node(node: usize, value: f64, message: String) -> Self {
+        Problem {
+            kind: ProblemKind::Value,
+            edge: None,
+            node: Some(node),
+            value: Some(value),
+            message,
+        }
+    }
+
+    fn edge(kind: ProblemKind, edge: usize, node: Option<usize>, value: f64, message: String) -> Self {
+        Problem {
+            kind,
+            edge: Some(edge),
+            node,
+            value: Some(value),
+            message,
+        }
+    }
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.edge, self.node) {
+            (Some(edge), Some(node)) => write!(f, "edge {}, node {}: {}", edge, node, self.message),
+            (Some(edge), None) => write!(f, "edge {}: {}", edge, self.message),
+            (None, Some(node)) => write!(f, "node {}: {}", node, self.message),
+            (None, None) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// What is wrong with a node temperature, if anything.
+pub fn temperature_problem(temperature: f64) -> Option<String> {
+    if temperature.is_finite() {
+        None
+    } else {
+        Some(format!("temperature is {}", temperature))
+    }
+}
+
+/// What is wrong with a node capacity, if anything.
+pub fn capacity_problem(capacity: f64) -> Option<String> {
+    if capacity.is_nan() || capacity <= 0.0 {
+        Some(format!("capacity must be positive, got {}", capacity))
+    } else {
+        None
+    }
+}
+
+/// What is wrong with a time step, if anything.
+pub fn time_step_problem(dt: f64) -> Option<String> {
+    if !dt.is_finite() || dt <= 0.0 {
+        Some(format!("time step must be finite and positive, got {}", dt))
+    } else {
+        None
+    }
+}
+
+/// What is wrong with a run duration, if anything.
+pub fn duration_problem(duration: f64) -> Option<String> {
+    if !duration.is_finite() || duration < 0.0 {
+        Some(format!("duration must be finite and not negative, got {}", duration))
+    } else {
+        None
+    }
+}
+
+/// What is wrong with the Crank–Nicolson damping, if anything.
+pub fn damping_problem(damping: f64) -> Option<String> {
+    if (0.0..=0.5).contains(&damping) {
+        None
+    } else {
+        Some(format!("damping must be in [0, 0.5], got {}", damping))
+    }
+}
+
+/// What is wrong with an edge parameter, if anything.
+pub fn parameter_problem(edge_type: EdgeType, parameter: f64) -> Option<String> {
+    if !parameter.is_finite() {
+        return Some(format!("parameter is {}", parameter));
+    }
+    match edge_type {
+        EdgeType::Transfer if parameter <= 0.0 => Some(format!(
+            "Transfer resistance must be positive, got {}",
+            parameter
+        )),
+        EdgeType::Radiation if parameter < 0.0 => Some(format!(
+            "Radiation constant must not be negative, got {}",
+            parameter
+        )),
+        EdgeType::Convection { .. } if parameter < 0.0 => Some(format!(
+            "Convection coefficient must not be negative, got {}",
+            parameter
+        )),
+        EdgeType::Advection if parameter < 0.0 => Some(format!(
+            "Advection capacity rate must not be negative, got {}",
+            parameter
+        )),
+        _ => None,
+    }
+}
+
+/// Check the node and edge arrays and collect every problem found.
+pub fn find_problems(
+    temperatures: ArrayView1<f64>,
+    capacities: ArrayView1<f64>,
+    parameters: ArrayView1<f64>,
+    connections: ArrayView2<usize>,
+    edge_types: ArrayView1<i32>,
+) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    let n_nodes = temperatures.len();
+    if capacities.len() != n_nodes {
+        problems.push(Problem::shape(format!(
+            "node arrays disagree in length: {} temperatures, {} capacities",
+            n_nodes,
+            capacities.len()
+        )));
+    }
+    let n_edges = edge_types.len();
+    if parameters.len() != n_edges || connections.nrows() != n_edges {
+        problems.push(Problem::shape(format!(
+            "edge arrays disagree in length: {} edge types, {} parameters, {} connections",
+            n_edges,
+            parameters.len(),
+            connections.nrows()
+        )));
+    }
+    if connections.nrows() > 0 && connections.ncols() != 2 {
+        problems.push(Problem::shape(format!(
+            "connections must have shape (E, 2), got ({}, {})",
+            connections.nrows(),
+            connections.ncols()
+        )));
+        // the rows cannot be checked
+        return problems;
+    }
+
+    for (node, &temperature) in temperatures.iter().enumerate() {
+        if let Some(message) = temperature_problem(temperature) {
+            problems.push(Problem::node(node, temperature, message));
+        }
+    }
+    for (node, &capacity) in capacities.iter().enumerate() {
+        if let Some(message) = capacity_problem(capacity) {
+            problems.push(Problem::node(node, capacity, message));
+        }
+    }
+
+    for (edge, ((&edge_type_int, &parameter), conn)) in edge_types
+        .iter()
+        .zip(parameters.iter())
+        .zip(connections.outer_iter())
+        .enumerate()
+    {
+        for &node in conn.iter() {
+            if node >= n_nodes {
+                problems.push(Problem::edge(
+                    ProblemKind::NodeIndex,
+                    edge,
+                    Some(node),
+                    node as f64,
+                    format!("node index out of range ({} nodes)", n_nodes),
+                ));
+            }
+        }
+
+        match EdgeType::from_i32(edge_type_int) {
+            None => problems.push(Problem::edge(
+                ProblemKind::EdgeType,
+                edge,
+                None,
+                edge_type_int as f64,
+                format!("undefined edge type {}", edge_type_int),
+            )),
+            Some(edge_type) => {
+                if let Some(message) = parameter_problem(edge_type, parameter) {
+                    problems.push(Problem::edge(ProblemKind::Value, edge, None, parameter, message));
+                }
+            }
+        }
+    }
+
+    problems
+}
+
+/// Validate the network arrays and decode the edges.
+///
+/// Raises a single `ChillValidationError` listing every problem found.
+pub fn validate_network(
+    temperatures: ArrayView1<f64>,
+    capacities: ArrayView1<f64>,
+    parameters: ArrayView1<f64>,
+    connections: ArrayView2<usize>,
+    edge_types: ArrayView1<i32>,
+) -> PyResult<Vec<Edge>> {
+    let problems = find_problems(temperatures, capacities, parameters, connections, edge_types);
+    if !problems.is_empty() {
+        return Err(validation_error(&problems));
+    }
+
+    Ok(edge_types
+        .iter()
+        .zip(parameters.iter())
+        .zip(connections.outer_iter())
+        .map(|((&edge_type_int, &parameter), conn)| Edge {
+            edge_type: EdgeType::from_i32(edge_type_int).unwrap(),
+            parameter,
+            n1: conn[0],
+            n2: conn[1],
+        })
+        .collect())
+}
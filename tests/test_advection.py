@@ -0,0 +1,101 @@
+import numpy as np
+import pytest
+from chill import Chill, ChillValidationError
+from chill.chill import Network, process
+
+
+def make_duct(rate=10.):
+    # coolant at 300 K heated by 100 W in each of three sections
+    temperatures = np.array([300., 300., 300., 300., 0.], dtype=np.float64)
+    capacities = np.array([np.inf, 100., 100., 100., np.inf], dtype=np.float64)
+    parameters = np.array([rate, rate, rate, 100., 100., 100.], dtype=np.float64)
+    connections = np.array([[0, 1], [1, 2], [2, 3], [4, 1], [4, 2], [4, 3]], dtype=np.uint64)
+    edge_types = np.array([4, 4, 4, 2, 2, 2], dtype=np.int32)
+    return temperatures, capacities, parameters, connections, edge_types
+
+
+def test_heat_flows_downstream():
+    temperatures = np.array([350., 300.], dtype=np.float64)
+    capacities = np.array([1000., 1000.], dtype=np.float64)
+    parameters = np.array([10.], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([4], dtype=np.int32)
+
+    network = Network(temperatures, capacities, parameters, connections, edge_types, 1.0)
+    assert network.edge_flows[0] == pytest.approx(500.)
+    network.step(1)
+    # the upstream node is not cooled by the edge that leaves it
+    assert network.temperatures[0] == 350.
+    assert network.temperatures[1] == pytest.approx(300.5)
+
+    with pytest.raises(ChillValidationError):
+        Network(temperatures, capacities, np.array([-1.]), connections, edge_types, 1.0)
+
+
+@pytest.mark.parametrize('method', ['euler', 'implicit'])
+def test_duct_outlet(method):
+    result, results = process(*make_duct(), 0.1, 20000, method=method, energy_balance=True)
+    energy = results['energy']
+
+    # each section adds 100 W / 10 W/K
+    assert result[1:4] == pytest.approx([310., 320., 330.])
+    # the rest of the heat left with the coolant
+    assert energy['stored'] == pytest.approx(100. * (10. + 20. + 30.))
+    assert abs(energy['error']) < 1e-2 * 6000.
+
+
+@pytest.mark.parametrize('method', ['euler', 'implicit'])
+def test_closed_loop_conserves_energy(method):
+    temperatures = np.array([400., 300., 300.], dtype=np.float64)
+    capacities = np.array([1000., 1000., 1000.], dtype=np.float64)
+    parameters = np.array([10., 10., 10.], dtype=np.float64)
+    connections = np.array([[0, 1], [1, 2], [2, 0]], dtype=np.uint64)
+    edge_types = np.array([4, 4, 4], dtype=np.int32)
+
+    result, results = process(temperatures, capacities, parameters, connections, edge_types,
+                              1.0, 5000, method=method, energy_balance=True)
+    energy = results['energy']
+
+    assert result == pytest.approx(np.full(3, 1000. / 3.))
+    assert energy['stored'] == pytest.approx(0., abs=1e-6)
+    assert abs(energy['error']) < 1e-6
+
+
+def test_time_varying_mass_flow():
+    network = Network(*make_duct(0.), 0.1)
+    # the pump doubles its flow after 50 s
+    for edge in range(3):
+        network.set_mass_flow(edge, lambda t: 0.01 if t < 50. else 0.02, 1000.)
+    assert network.parameters[0] == pytest.approx(10.)
+    network.step(2000)
+    assert network.parameters[0] == pytest.approx(20.)
+    assert network.temperatures[3] == pytest.approx(315., abs=1e-3)
+
+    result = process(*make_duct(0.), 0.1, 2000,
+                     mass_flows={edge: ((np.array([0., 50.]), np.array([0.01, 0.02])), 1000.)
+                                 for edge in range(3)})
+    assert result[3] == pytest.approx(315., abs=1e-3)
+
+    with pytest.raises(ChillValidationError):
+        network.set_mass_flow(0, -0.01, 1000.)
+    with pytest.raises(ChillValidationError):
+        network.set_mass_flow(0, 0.01, 0.)
+    with pytest.raises(ChillValidationError):
+        network.set_mass_flow(3, 0.01, 1000.)
+
+
+def test_chill_pumped_loop():
+    c = Chill(dt=0.1)
+    cold_plate = c.define_node(300., 100.)
+    radiator = c.define_node(300., 100.)
+    space = c.define_boundary(250.)
+    c.define_heater(cold_plate, 50.)
+    c.define_thermal_conduction(radiator, space, 0.1)
+    c.define_advection(cold_plate, radiator, 0.005, 1000.)
+    c.define_advection(radiator, cold_plate, 0.005, 1000.)
+    c.setup()
+    c.run(50000)
+
+    # the radiator rejects the 50 W, and the loop carries them at 5 W/K
+    assert c.temperatures[1] == pytest.approx(255., abs=1e-3)
+    assert c.temperatures[0] == pytest.approx(265., abs=1e-3)
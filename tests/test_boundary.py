@@ -0,0 +1,91 @@
+import numpy as np
+import pytest
+from chill import Chill, Profile, ChillValidationError
+from chill.chill import Network, process
+
+
+def make_mounted_plate():
+    temperatures = np.array([300., 300.], dtype=np.float64)
+    capacities = np.array([1000., 10.], dtype=np.float64)
+    parameters = np.array([1.], dtype=np.float64)
+    connections = np.array([[0, 1], ], dtype=np.uint64)
+    edge_types = np.array([0], dtype=np.int32)
+    return temperatures, capacities, parameters, connections, edge_types
+
+
+def test_constant_boundary_is_not_integrated():
+    arrays = make_mounted_plate()
+
+    temperatures = process(*arrays, 0.1, 10000, boundaries={0: 250.})
+
+    assert temperatures[0] == 250.
+    assert temperatures[1] == pytest.approx(250., abs=1e-6)
+
+
+@pytest.mark.parametrize('method', ['euler', 'rk4', 'implicit', 'crank_nicolson'])
+def test_boundary_schedule(method):
+    arrays = make_mounted_plate()
+    network = Network(*arrays, 1.0, method=method)
+    network.set_boundary(0, (np.array([0., 100.]), np.array([300., 400.])))
+    assert network.capacities[0] == 1000.
+
+    network.step(50)
+    assert network.temperatures[0] == pytest.approx(350.)
+    # the plate lags the ramp by its time constant R * C = 10 s
+    assert network.temperatures[1] == pytest.approx(340., abs=1.)
+
+    network.step(100)
+    assert network.temperatures[0] == 400.
+
+    network.clear_boundary(0)
+    assert network.boundary_nodes == []
+    assert network.capacities[0] == 1000.
+    with pytest.raises(ChillValidationError):
+        network.clear_boundary(0)
+    with pytest.raises(ChillValidationError):
+        network.set_boundary(2, 300.)
+
+
+def test_guard_skips_boundary_nodes():
+    arrays = make_mounted_plate()
+    network = Network(*arrays, 1.0, max_delta=10.)
+    # the prescribed jump of the boundary is not a failure
+    network.set_boundary(0, 250.)
+    network.step(1)
+    assert network.temperatures[0] == 250.
+
+
+def test_chill_boundary_node():
+    c = Chill(dt=1.0)
+    shroud = c.define_boundary((np.array([0., 1000.]), np.array([300., 100.])), name='shroud')
+    plate = c.define_node(300., 10., name='plate')
+    c.define_thermal_conduction(shroud, plate, 1.)
+    c.setup()
+    c.run(500)
+
+    assert c.temperatures[0] == pytest.approx(200.)
+    assert c.temperatures[1] == pytest.approx(202., abs=0.1)
+
+
+def test_boundary_function_of_time():
+    arrays = make_mounted_plate()
+    ambient = Profile([0., 43200.], [280., 300.], period=86400.)
+
+    shroud = process(*arrays, 10.0, 4320, boundaries={0: ambient})
+    diurnal = process(*arrays, 10.0, 4320, boundaries={0: lambda t: 290. - 10. * np.cos(2 * np.pi * t / 86400.)})
+
+    assert shroud[0] == pytest.approx(300.)
+    assert diurnal[0] == pytest.approx(300.)
+    assert diurnal[1] == pytest.approx(300., abs=0.1)
+
+
+def test_boundary_function_errors_are_raised():
+    arrays = make_mounted_plate()
+
+    def broken(t):
+        if t > 5.:
+            raise KeyError('chiller offline')
+        return 250.
+
+    with pytest.raises(KeyError):
+        process(*arrays, 1.0, 10, boundaries={0: broken}, on_failure='stop')
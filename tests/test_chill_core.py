@@ -28,3 +28,129 @@ def test_simple_heat_transfer():
     assert abs(c.temperatures[0] - c.temperatures[1]) < 1.e-3
     assert abs(c.temperatures[1] - c.temperatures[2]) < 1.e-3
 
+
+def test_execute_skips_edge_flows_by_default():
+    c = Chill(dt=1.0)
+    node0 = c.define_node(400., 1000.)
+    node1 = c.define_node(300., 1000.)
+    c.define_thermal_conduction(node0, node1, 10.)
+    c.setup()
+    c.execute(100, 10)
+
+    assert len(c.temperatures_history) == 10
+    assert c.edge_flows_history == []
+
+
+def test_execute_records_every_interval_across_progress_updates():
+    c = Chill(dt=1.0)
+    node0 = c.define_node(400., 1000.)
+    node1 = c.define_node(300., 1000.)
+    c.define_thermal_conduction(node0, node1, 10.)
+    c.setup()
+    c.execute(1000, 4)
+
+    # more intervals than progress updates
+    assert len(c.temperatures_history) == 250
+    assert c.times_history == pytest.approx([4. * (i + 1) for i in range(250)])
+    assert c.time == 1000.
+
+
+def test_execute_records_edge_flows_on_request():
+    c = Chill(dt=1.0)
+    node0 = c.define_node(400., 1000.)
+    node1 = c.define_node(300., 1000.)
+    c.define_thermal_conduction(node0, node1, 10.)
+    c.setup()
+    c.execute(100, 10, edge_flows=True)
+
+    assert len(c.edge_flows_history) == 10
+    assert c.edge_flows_history[-1][0] == pytest.approx((c.temperatures[0] - c.temperatures[1]) / 10.)
+
+
+def test_edited_arrays_reach_the_network():
+    import numpy as np
+    c = Chill(dt=1.0)
+    node0 = c.define_node(400., 1000.)
+    node1 = c.define_node(300., 1000.)
+    c.define_thermal_conduction(node0, node1, 10.)
+    c.setup()
+    c.temperatures[0] = 350.
+    c.parameters[0] = 0.
+    c.run(10)
+
+    assert c.temperatures[0] == 350.
+    assert c.network.parameters[0] == 0.
+
+    c.parameters[0] = 10.
+    c.capacities[1] = np.inf
+    c.run(10)
+
+    assert c.temperatures[0] < 350.
+    assert c.temperatures[1] == 300.
+
+
+def test_network_matches_process():
+    import numpy as np
+    from chill.chill import Network, process
+
+    temperatures = np.array([500., 300., 300.], dtype=np.float64)
+    capacities = np.array([1000., 1000., 1000.], dtype=np.float64)
+    parameters = np.array([0.1, 0.1], dtype=np.float64)
+    connections = np.array([[0, 1], [1, 2]], dtype=np.uint64)
+    edge_types = np.array([0, 0], dtype=np.int32)
+
+    network = Network(temperatures, capacities, parameters, connections, edge_types, 0.1)
+    for _ in range(10):
+        network.step(100)
+    expected = process(temperatures, capacities, parameters, connections, edge_types, 0.1, 1000)
+
+    assert np.allclose(network.temperatures, expected)
+    assert abs(network.time - 100.) < 1.e-9
+
+    network.set_parameter(0, 1.e9)
+    before = network.temperatures
+    network.step(1)
+    assert abs(network.temperatures[0] - before[0]) < 1.e-6
+
+
+def test_history_is_recorded_in_one_call():
+    import numpy as np
+    from chill.chill import process, process_history
+
+    temperatures = np.array([500., 300., 300.], dtype=np.float64)
+    capacities = np.array([1000., 1000., 1000.], dtype=np.float64)
+    parameters = np.array([0.1, 0.1], dtype=np.float64)
+    connections = np.array([[0, 1], [1, 2]], dtype=np.uint64)
+    edge_types = np.array([0, 0], dtype=np.int32)
+
+    times, history = process_history(temperatures, capacities, parameters, connections, edge_types,
+                                     0.1, 1000, record_every=100)
+
+    assert history.shape == (10, 3)
+    assert np.allclose(times, np.arange(1, 11) * 10.)
+    expected = process(temperatures, capacities, parameters, connections, edge_types, 0.1, 300)
+    assert np.allclose(history[2], expected)
+
+
+def test_edge_flows():
+    import numpy as np
+    from chill.chill import process, process_history
+
+    temperatures = np.array([400., 300., 300.], dtype=np.float64)
+    capacities = np.array([1000., 1000., np.inf], dtype=np.float64)
+    parameters = np.array([10., 2.], dtype=np.float64)
+    connections = np.array([[0, 1], [2, 0]], dtype=np.uint64)
+    edge_types = np.array([0, 2], dtype=np.int32)
+
+    _, results = process(temperatures, capacities, parameters, connections, edge_types,
+                         0.1, 10, edge_flows=True)
+    mean_flows = results['edge_flows']
+    assert mean_flows.shape == (2, )
+    assert abs(mean_flows[0] - 10.) < 0.1
+    assert mean_flows[1] == 2.
+
+    times, history, flows = process_history(temperatures, capacities, parameters, connections, edge_types,
+                                            0.1, 100, record_every=10, edge_flows=True)
+    assert flows.shape == (10, 2)
+    assert np.allclose(flows[:, 0], (history[:, 0] - history[:, 1]) / 10.)
+    assert np.all(flows[:, 1] == 2.)
@@ -0,0 +1,77 @@
+import numpy as np
+import pytest
+from chill import Chill, Profile, ChillValidationError
+from chill.chill import Network, process
+
+
+def make_link(t0=300., t1=100.):
+    temperatures = np.array([t0, t1], dtype=np.float64)
+    capacities = np.array([np.inf, np.inf], dtype=np.float64)
+    parameters = np.array([1.], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([0], dtype=np.int32)
+    return temperatures, capacities, parameters, connections, edge_types
+
+
+def test_mean_and_integral_conductivity():
+    network = Network(*make_link(), 1.0)
+    table = (np.array([100., 200., 300.]), np.array([1., 1., 3.]))
+
+    network.set_conductivity(0, table, 2.)
+    # k at 200 K
+    assert network.parameters[0] == pytest.approx(1 / 2.)
+    network.set_conductivity(0, Profile(*table), 2., mode='integral')
+    # (100 K * 1 + 100 K * 2) / 200 K
+    assert network.parameters[0] == pytest.approx(1 / 3.)
+
+    network.set_conductivity(0, lambda t: t**2 / 1e4, 1., mode='integral')
+    assert network.parameters[0] == pytest.approx(6e6 / 26e6)
+
+    network.step(10)
+    assert network.edge_flows[0] == pytest.approx(26e6 / 6e6 * 200.)
+
+    with pytest.raises(ChillValidationError):
+        network.set_conductivity(0, table, 2., mode='median')
+    with pytest.raises(ChillValidationError):
+        network.set_conductivity(0, table, 0.)
+    with pytest.raises(ChillValidationError):
+        network.set_conductivity(1, table, 2.)
+
+
+def test_conductivity_follows_temperature():
+    temperatures = np.array([300., 10.], dtype=np.float64)
+    capacities = np.array([100., 100.], dtype=np.float64)
+    parameters = np.array([1.], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([0], dtype=np.int32)
+    # conductivity falling by two orders of magnitude towards low temperature
+    conductivity = (np.array([10., 300.]), np.array([0.01, 1.]))
+
+    result, results = process(temperatures, capacities, parameters, connections, edge_types,
+                              0.1, 20000, energy_balance=True,
+                              conductivities={0: (conductivity, 1., 'integral')})
+    energy = results['energy']
+
+    assert result[0] == pytest.approx(155., abs=0.1)
+    assert result[1] == pytest.approx(155., abs=0.1)
+    assert abs(energy['error']) < 1e-6
+
+    varying = process(temperatures, capacities, parameters, connections, edge_types,
+                      0.1, 2000, conductivities={0: (conductivity, 1.)})
+    fixed = process(temperatures, capacities, np.array([1 / 0.01]), connections, edge_types,
+                    0.1, 2000)
+    assert varying[0] - varying[1] < 0.9 * (fixed[0] - fixed[1])
+
+
+def test_chill_conductive_link():
+    c = Chill(dt=0.1)
+    hot = c.define_boundary(300.)
+    cold = c.define_node(10., 100.)
+    c.define_conductive_link(hot, cold, lambda t: 1e-2 * t, area=1e-4, length=1e-2)
+    c.setup()
+    assert c.parameters[0] == pytest.approx(1 / (1e-2 * 1.55))
+    c.run(100)
+
+    # the conductance follows the cooling link
+    assert c.parameters[0] == c.network.parameters[0]
+    assert c.parameters[0] < 1 / (1e-2 * 1.55)
@@ -0,0 +1,115 @@
+import numpy as np
+import pytest
+from chill import Chill, PidController, Thermostat, ChillValidationError
+from chill.chill import Network, process
+
+
+def make_heated_plate():
+    # ambient at 250 K, plate of 100 J/K losing 1 W/K, heater feeding the plate
+    temperatures = np.array([250., 300., 300.], dtype=np.float64)
+    capacities = np.array([np.inf, 100., np.inf], dtype=np.float64)
+    parameters = np.array([1., 0.], dtype=np.float64)
+    connections = np.array([[0, 1], [2, 1]], dtype=np.uint64)
+    edge_types = np.array([0, 2], dtype=np.int32)
+    return temperatures, capacities, parameters, connections, edge_types
+
+
+def test_thermostat_hysteresis():
+    arrays = make_heated_plate()
+    thermostat = Thermostat(1, 1, 100., 290., 295.)
+
+    network = Network(*arrays, 0.1)
+    network.add_controller(thermostat)
+    times, history = network.record(20000, 10)
+
+    plate = history[times > 100., 1]
+    assert plate.min() > 289.5
+    assert plate.max() < 295.5
+
+    report, = network.controllers
+    assert report['type'] == 'thermostat'
+    assert report['edge'] == 1
+    states = report['switch_states']
+    assert states[0] is True
+    assert all(a != b for a, b in zip(states, states[1:]))
+    assert len(states) > 20
+    # on average the heater covers the loss of about 42.5 W
+    assert report['duty_cycle'] == pytest.approx(0.425, abs=0.03)
+
+
+def test_thermostat_in_process():
+    arrays = make_heated_plate()
+
+    temperatures, results = process(*arrays, 0.1, 20000, energy_balance=True,
+                                    controllers=[Thermostat(1, 1, 100., 290., 295.)])
+
+    assert 289.5 < temperatures[1] < 295.5
+    report, = results['controllers']
+    energy = results['energy']
+    assert energy['heat_input'] == pytest.approx(100. * report['on_time'])
+    assert abs(energy['error']) < 1.
+
+    with pytest.raises(ChillValidationError):
+        process(*arrays, 0.1, 10, controllers=[Thermostat(0, 1, 100., 290., 295.)])
+    with pytest.raises(ChillValidationError):
+        Thermostat(1, 1, 100., 295., 290.)
+
+
+def test_chill_thermostat():
+    c = Chill(dt=0.1)
+    ambient = c.define_boundary(250.)
+    plate = c.define_node(300., 100.)
+    c.define_thermal_conduction(ambient, plate, 1.)
+    c.define_thermostat(plate, plate, 100., 290., 295.)
+    c.setup()
+    c.run(20000)
+
+    assert 289.5 < c.temperatures[1] < 295.5
+    report, = c.controller_states()
+    assert 0.3 < report['duty_cycle'] < 0.55
+
+
+def test_pid_holds_setpoint():
+    arrays = make_heated_plate()
+    pid = PidController(1, 1, 293., 20., ki=0.5, max_power=100., period=1.)
+
+    temperatures, results = process(*arrays, 0.1, 20000, controllers=[pid])
+
+    assert temperatures[1] == pytest.approx(293., abs=0.05)
+    report, = results['controllers']
+    assert report['type'] == 'pid'
+    # the heater covers the loss of 43 W to the ambient
+    assert report['power'] == pytest.approx(43., abs=0.5)
+    assert not report['saturated']
+    assert report['samples'] == pytest.approx(2001, abs=2)
+
+
+def test_pid_anti_windup():
+    arrays = make_heated_plate()
+    network = Network(*arrays, 0.1)
+    network.add_controller(PidController(1, 1, 293., 20., ki=0.5, max_power=30.))
+
+    network.step(20000)
+
+    report, = network.controllers
+    assert report['saturated']
+    assert report['power'] == 30.
+    assert network.temperatures[1] == pytest.approx(280., abs=0.01)
+    # the integral stopped once the output saturated
+    assert report['integral'] <= 60.
+
+    with pytest.raises(ChillValidationError):
+        PidController(1, 1, 293., 20., min_power=10., max_power=5.)
+
+
+def test_chill_pid_heater():
+    c = Chill(dt=0.1)
+    ambient = c.define_boundary(250.)
+    plate = c.define_node(300., 100.)
+    c.define_thermal_conduction(ambient, plate, 1.)
+    c.define_pid_heater(plate, plate, 293., 20., ki=0.5, max_power=100., period=0.5)
+    c.setup()
+    c.execute(2000., 100.)
+
+    assert c.temperatures[1] == pytest.approx(293., abs=0.05)
+    assert c.controller_states()[0]['type'] == 'pid'
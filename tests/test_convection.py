@@ -0,0 +1,101 @@
+import numpy as np
+import pytest
+from chill import Chill, Convection, Fluid, ChillValidationError
+from chill.chill import Network, process
+
+
+def make_surface(parameter=2.):
+    # a surface at 350 K in fluid at 300 K
+    temperatures = np.array([350., 300.], dtype=np.float64)
+    capacities = np.array([np.inf, np.inf], dtype=np.float64)
+    parameters = np.array([parameter], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([3], dtype=np.int32)
+    return temperatures, capacities, parameters, connections, edge_types
+
+
+def test_power_law():
+    network = Network(*make_surface(), 1.0)
+    # natural convection by default, q ~ dT^1.25
+    assert network.edge_flows[0] == pytest.approx(2. * 50.**1.25)
+
+    network.set_convection(0, Convection.power_law(2., exponent=1 / 3, area=0.5))
+    assert network.edge_flows[0] == pytest.approx(50.**(4 / 3))
+
+    # a plain parameter is back on the default law
+    network.set_parameter(0, 2.)
+    assert network.edge_flows[0] == pytest.approx(2. * 50.**1.25)
+    network.set_convection(0, Convection.vertical_plate(0.2, 0.04, Fluid.air()))
+    network.parameters = np.array([2.])
+    assert network.edge_flows[0] == pytest.approx(2. * 50.**1.25)
+
+    with pytest.raises(ChillValidationError):
+        Network(*make_surface(-1.), 1.0)
+    with pytest.raises(ChillValidationError):
+        Convection.power_law(-2.)
+    with pytest.raises(ChillValidationError):
+        Convection.vertical_plate(0., 0.04, Fluid.air())
+    with pytest.raises(ChillValidationError):
+        network.set_convection(1, Convection.power_law(2.))
+
+
+def test_correlations():
+    fluid = Fluid(0.0263, 15.89e-6, 0.707)
+    k, nu, pr, beta = 0.0263, 15.89e-6, 0.707, 1 / 325.
+
+    rayleigh = 9.80665 * beta * 50. * 0.2**3 / nu**2 * pr
+    nusselt = (0.825 + 0.387 * rayleigh**(1 / 6) / (1 + (0.492 / pr)**(9 / 16))**(8 / 27))**2
+    plate = Convection.vertical_plate(0.2, 0.04, fluid)
+    assert plate.coefficient(350., 300.) == pytest.approx(nusselt * k / 0.2)
+    assert plate.parameter(350., 300.) == pytest.approx(0.04 * nusselt * k / 0.2)
+
+    rayleigh = 9.80665 * beta * 50. * 0.05**3 / nu**2 * pr
+    nusselt = (0.60 + 0.387 * rayleigh**(1 / 6) / (1 + (0.559 / pr)**(9 / 16))**(8 / 27))**2
+    cylinder = Convection.horizontal_cylinder(0.05, 0.1, fluid)
+    assert cylinder.coefficient(350., 300.) == pytest.approx(nusselt * k / 0.05)
+
+    laminar = Convection.forced_plate(0.5, 0.25, 2., fluid)
+    reynolds = 2. * 0.5 / nu
+    assert laminar.coefficient(350., 300.) == pytest.approx(0.664 * reynolds**0.5 * pr**(1 / 3) * k / 0.5)
+    mixed = Convection.forced_plate(1., 1., 20., fluid)
+    reynolds = 20. / nu
+    assert mixed.coefficient(350., 300.) == pytest.approx((0.037 * reynolds**0.8 - 871.) * pr**(1 / 3) * k)
+
+    # the air table at the film temperature of 325 K
+    air = Convection.vertical_plate(0.2, 0.04, Fluid.air())
+    interpolated = Convection.vertical_plate(0.2, 0.04, Fluid(0.02815, 18.405e-6, 0.7035))
+    assert air.coefficient(350., 300.) == pytest.approx(interpolated.coefficient(350., 300.))
+
+
+@pytest.mark.parametrize('method', ['euler', 'implicit'])
+def test_convective_cooling(method):
+    temperatures = np.array([400., 300.], dtype=np.float64)
+    capacities = np.array([1000., np.inf], dtype=np.float64)
+    parameters = np.array([1.], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([3], dtype=np.int32)
+    plate = Convection.vertical_plate(0.2, 0.04, Fluid.air())
+
+    network = Network(temperatures, capacities, parameters, connections, edge_types, 1.0, method=method)
+    network.set_convection(0, plate)
+    network.step(600)
+
+    cooled = network.temperatures[0]
+    assert 300. < cooled < 400.
+    # the coefficient follows the temperature difference
+    assert network.parameters[0] == pytest.approx(plate.parameter(cooled, 300.), rel=1e-2)
+
+    result = process(temperatures, capacities, parameters, connections, edge_types, 1.0, 600,
+                     method=method, convections={0: plate})
+    assert result[0] == pytest.approx(cooled)
+
+
+def test_chill_convection():
+    c = Chill(dt=1.0)
+    air = c.define_boundary(300.)
+    plate = c.define_node(400., 1000.)
+    c.define_convection(plate, air, Convection.vertical_plate(0.2, 0.04, Fluid.air()))
+    c.setup()
+    c.run(600)
+
+    assert 300. < c.temperatures[1] < 400.
@@ -0,0 +1,40 @@
+import numpy as np
+import pytest
+from chill.chill import Network, process
+
+
+def make_heater_chain():
+    # heater -> plate -> sink (fixed)
+    temperatures = np.array([300., 300., 250.], dtype=np.float64)
+    capacities = np.array([np.inf, 1000., np.inf], dtype=np.float64)
+    parameters = np.array([5., 2.], dtype=np.float64)
+    connections = np.array([[0, 1], [1, 2]], dtype=np.uint64)
+    edge_types = np.array([2, 0], dtype=np.int32)
+    return temperatures, capacities, parameters, connections, edge_types
+
+
+@pytest.mark.parametrize('method', ['euler', 'rk4', 'implicit', 'crank_nicolson'])
+def test_energy_is_accounted_for(method):
+    temperatures, capacities, parameters, connections, edge_types = make_heater_chain()
+
+    final, results = process(temperatures, capacities, parameters, connections, edge_types,
+                             1.0, 1000, method=method, energy_balance=True)
+    report = results['energy']
+
+    assert report['heat_input'] == pytest.approx(5000.)
+    assert report['stored'] == pytest.approx(1000. * (final[1] - 300.))
+    assert report['boundary'] < 0.
+    assert abs(report['error']) < 1.e-2 * report['heat_input']
+
+
+def test_balance_error_shrinks_with_dt():
+    temperatures, capacities, parameters, connections, edge_types = make_heater_chain()
+
+    errors = []
+    for dt in [2.0, 1.0]:
+        network = Network(temperatures, capacities, parameters, connections, edge_types, dt,
+                          energy_balance=True)
+        network.step(int(1000 / dt))
+        errors.append(abs(network.energy_balance['error']))
+
+    assert errors[1] < 0.6 * errors[0]
@@ -0,0 +1,113 @@
+import numpy as np
+import pytest
+from chill.chill import process, ChillInstabilityError
+
+
+def make_heated_node():
+    temperatures = np.array([300., 300.], dtype=np.float64)
+    capacities = np.array([np.inf, 1.], dtype=np.float64)
+    parameters = np.array([1.], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([2], dtype=np.int32)
+    return temperatures, capacities, parameters, connections, edge_types
+
+
+def test_temperature_range_raises_with_step_and_node():
+    arrays = make_heated_node()
+
+    with pytest.raises(ChillInstabilityError) as error:
+        process(*arrays, 1.0, 100, temperature_range=(0., 350.))
+
+    assert error.value.node_index == 1
+    assert error.value.step == 50
+    assert error.value.value == 351.
+
+
+def test_max_delta():
+    arrays = make_heated_node()
+
+    with pytest.raises(ChillInstabilityError) as error:
+        process(*arrays, 1.0, 10, max_delta=0.5)
+
+    assert error.value.node_index == 1
+    assert error.value.step == 0
+
+
+def test_stop_keeps_partial_state():
+    arrays = make_heated_node()
+
+    temperatures, results = process(*arrays, 1.0, 100, temperature_range=(0., 350.), on_failure='stop')
+
+    assert temperatures[1] == 350.
+    failure = results['failure']
+    assert failure['step'] == 50
+    assert failure['node_index'] == 1
+    assert failure['time'] == 50.
+
+    temperatures, results = process(*arrays, 1.0, 10, on_failure='stop')
+    assert temperatures[1] == 310.
+    assert results['failure'] is None
+
+
+def test_nan_is_caught():
+    temperatures, capacities, parameters, connections, edge_types = make_heated_node()
+    parameters[0] = 1e308
+
+    with pytest.raises(ChillInstabilityError) as error:
+        process(temperatures, capacities, parameters, connections, edge_types, 1e10, 1,
+                max_delta=np.inf)
+
+    assert error.value.node_index == 1
+
+
+def test_error_carries_last_good_state():
+    arrays = make_heated_node()
+
+    with pytest.raises(ChillInstabilityError) as error:
+        process(*arrays, 1.0, 100, temperature_range=(0., 350.))
+
+    assert np.allclose(error.value.temperatures, [300., 350.])
+    assert error.value.time == 50.
+
+
+def test_chill_keeps_partial_state():
+    from chill import Chill
+
+    c = Chill(dt=1.0, temperature_range=(0., 350.))
+    heater = c.define_node(300., np.inf)
+    plate = c.define_node(300., 1.)
+    c.define_thermal_input(heater, plate, 1.)
+    c.setup()
+
+    with pytest.raises(ChillInstabilityError):
+        c.run(100)
+
+    assert c.temperatures[1] == 350.
+    assert c.time == 50.
+
+
+def test_step_halving():
+    arrays = make_heated_node()
+
+    temperatures, results = process(*arrays, 1.0, 10, max_delta=0.3, min_dt=0.1)
+
+    assert temperatures[1] == pytest.approx(310.)
+    # each step fails at 1 and twice at 0.5, then passes as quarters
+    assert results == {'halvings': 30}
+
+    with pytest.raises(ChillInstabilityError):
+        process(*arrays, 1.0, 10, max_delta=0.3, min_dt=0.5)
+
+
+def test_chill_step_halving():
+    from chill import Chill
+
+    c = Chill(dt=1.0, max_delta=0.3, min_dt=0.1)
+    heater = c.define_node(300., np.inf)
+    plate = c.define_node(300., 1.)
+    c.define_thermal_input(heater, plate, 1.)
+    c.setup()
+    c.run(10)
+
+    assert c.temperatures[1] == pytest.approx(310.)
+    assert c.network.halvings == 30
@@ -0,0 +1,78 @@
+import numpy as np
+import pytest
+from chill import Chill
+from chill.chill import Network, process
+
+
+@pytest.mark.parametrize('method', ['euler', 'rk4', 'implicit', 'crank_nicolson'])
+def test_heating_follows_enthalpy(method):
+    temperatures = np.array([300., 10.], dtype=np.float64)
+    capacities = np.array([np.inf, 1.], dtype=np.float64)
+    parameters = np.array([10.], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([2], dtype=np.int32)
+    network = Network(temperatures, capacities, parameters, connections, edge_types, 1.0, method=method)
+
+    # C = T, so 10 W for 100 s give T^2 / 2 = 10^2 / 2 + 1000
+    network.set_heat_capacity(1, lambda t: t)
+    network.step(100)
+
+    assert network.temperatures[1] == pytest.approx(np.sqrt(2100.), rel=1e-9)
+    assert network.capacities[1] == pytest.approx(network.temperatures[1], rel=1e-2)
+
+
+def test_capacity_function_calls_per_step():
+    temperatures = np.array([300., 10.], dtype=np.float64)
+    capacities = np.array([np.inf, 1.], dtype=np.float64)
+    parameters = np.array([10.], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([2], dtype=np.int32)
+    network = Network(temperatures, capacities, parameters, connections, edge_types, 1.0)
+    calls = []
+
+    def capacity(t):
+        calls.append(t)
+        return t
+
+    network.set_heat_capacity(1, capacity)
+    calls.clear()
+    network.step(10)
+
+    # one Simpson average of C costs 17 calls, a step needs a handful of them
+    assert 0 < len(calls) < 10 * 150
+    assert network.temperatures[1] == pytest.approx(np.sqrt(300.), rel=1e-9)
+
+
+def test_cooldown_conserves_energy():
+    temperatures = np.array([300., 10.], dtype=np.float64)
+    capacities = np.array([400., 100.], dtype=np.float64)
+    parameters = np.array([1.], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([0], dtype=np.int32)
+    # cp of a metal falling steeply at low temperature
+    capacity = (np.array([10., 100., 300.]), np.array([1., 100., 400.]))
+
+    result, results = process(temperatures, capacities, parameters, connections, edge_types,
+                              0.1, 50000, energy_balance=True, heat_capacities={0: capacity})
+    energy = results['energy']
+
+    assert result[0] == pytest.approx(result[1], abs=1e-3)
+    assert abs(energy['error']) < 1e-6
+    # the constant-capacity node received what the other one lost
+    assert energy['stored'] == pytest.approx(0., abs=1e-6)
+
+    constant = process(temperatures, capacities, parameters, connections, edge_types, 0.1, 50000)
+    assert result[0] < constant[0] - 3.
+
+
+def test_chill_temperature_dependent_node():
+    c = Chill(dt=0.1)
+    shroud = c.define_boundary(20.)
+    plate = c.define_node(300., (np.array([10., 100., 300.]), np.array([1., 100., 400.])))
+    c.define_thermal_conduction(shroud, plate, 1.)
+    c.setup()
+    assert c.capacities[1] == 400.
+    c.run(10000)
+
+    assert c.temperatures[1] < 100.
+    assert c.network.capacities[1] < 100.
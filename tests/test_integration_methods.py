@@ -0,0 +1,117 @@
+import numpy as np
+import pytest
+from chill import Chill, Convection, ChillValidationError
+from chill.chill import Network, process, process_adaptive, stable_time_step
+
+
+def cooling_network():
+    temperatures = np.array([500., 300.], dtype=np.float64)
+    capacities = np.array([100., np.inf], dtype=np.float64)
+    parameters = np.array([0.1], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([Chill.TYPE_TRANSFER], dtype=np.int32)
+    return temperatures, capacities, parameters, connections, edge_types
+
+
+@pytest.mark.parametrize('method', ['euler', 'rk4', 'implicit', 'crank_nicolson'])
+def test_methods_follow_exponential_decay(method):
+    dt, steps = 0.1, 100
+    result = process(*cooling_network(), dt, steps, method=method)
+
+    tau = 100. * 0.1
+    exact = 300. + 200. * np.exp(-dt * steps / tau)
+    assert abs(result[0] - exact) < 0.5
+    assert result[1] == 300.
+
+
+def test_crank_nicolson_is_second_order():
+    tau = 100. * 0.1
+    exact = 300. + 200. * np.exp(-10. / tau)
+    errors = [abs(process(*cooling_network(), dt, int(10. / dt), method='crank_nicolson')[0] - exact)
+              for dt in (0.5, 0.25)]
+    assert errors[0] / errors[1] == pytest.approx(4., rel=0.05)
+
+
+def test_rk4_is_more_accurate_than_euler():
+    tau = 100. * 0.1
+    exact = 300. + 200. * np.exp(-10. / tau)
+    euler_error = abs(process(*cooling_network(), 1., 10, method='euler')[0] - exact)
+    rk4_error = abs(process(*cooling_network(), 1., 10, method='rk4')[0] - exact)
+    assert rk4_error < 1.e-3 * euler_error
+
+
+def test_implicit_is_stable_for_large_steps():
+    result = process(*cooling_network(), 1000., 10, method='implicit')
+    assert abs(result[0] - 300.) < 1.e-6
+
+
+@pytest.mark.parametrize('method, dt', [('euler', 1.), ('rk4', 1.), ('implicit', 1.), ('implicit', 1000.),
+                                        ('crank_nicolson', 1.), ('crank_nicolson', 1000.)])
+def test_mean_edge_flows_carry_the_energy_released(method, dt):
+    network = Network(*cooling_network(), dt, method=method, edge_flows=True)
+    network.step(10)
+
+    released = 100. * (500. - network.temperatures[0])
+    assert network.mean_edge_flows[0] * network.time == pytest.approx(released)
+
+
+def test_adaptive_reaches_end_time_within_tolerance():
+    result, stats = process_adaptive(*cooling_network(), 100., atol=1.e-8, rtol=1.e-8)
+
+    exact = 300. + 200. * np.exp(-100. / 10.)
+    assert abs(result[0] - exact) < 1.e-5
+    assert stats['accepted_steps'] > 0
+    assert stats['min_dt'] <= stats['max_dt']
+
+
+def test_chill_adaptive_follows_the_network():
+    c = Chill(dt=1.0)
+    plate = c.define_node(300., 100.)
+    air = c.define_boundary(300.)
+    c.define_heater(plate, 50.)
+    c.define_convection(plate, air, Convection.power_law(2., exponent=1 / 3, area=0.5))
+    c.setup()
+    c.run_adaptive(2000.)
+
+    # q = h * A * dT^(4/3)
+    assert c.temperatures[0] == pytest.approx(300. + 50.**0.75, abs=1e-3)
+    assert c.time == 2000.
+
+
+def test_adaptive_rejects_a_varying_network():
+    c = Chill()
+    plate = c.define_node(300., 100.)
+    space = c.define_boundary((np.array([0., 100.]), np.array([300., 200.])))
+    c.define_thermal_conduction(plate, space, 1.)
+    c.setup()
+
+    with pytest.raises(ChillValidationError):
+        c.run_adaptive(100.)
+    assert c.temperatures[0] == 300.
+    assert c.time == 0.
+
+
+@pytest.mark.parametrize('duration', [np.nan, np.inf, -1.])
+def test_adaptive_rejects_an_invalid_duration(duration):
+    c = Chill()
+    plate = c.define_node(400., 100.)
+    space = c.define_boundary(300.)
+    c.define_thermal_conduction(plate, space, 1.)
+    c.setup()
+
+    with pytest.raises(ChillValidationError):
+        c.run_adaptive(duration)
+    assert c.temperatures[0] == 400.
+    assert c.time == 0.
+
+
+def test_stable_time_step_limits_explicit_euler():
+    network = cooling_network()
+    limit = stable_time_step(*network)
+    assert limit == pytest.approx(100. * 0.1)
+
+    with pytest.raises(RuntimeError):
+        process(*network, 2 * limit, 10, dt_limit='enforce')
+
+    result = process(*network, 5 * limit, 10, dt_limit='substep')
+    assert 300. < result[0] < 500.
@@ -0,0 +1,73 @@
+import numpy as np
+import pytest
+from chill import Chill, ChillValidationError
+from chill.chill import Network, process
+
+
+def make_heated_pcm():
+    # a 10 W heater feeding a node of 10 J/K
+    temperatures = np.array([300., 290.], dtype=np.float64)
+    capacities = np.array([np.inf, 10.], dtype=np.float64)
+    parameters = np.array([10.], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([2], dtype=np.int32)
+    return temperatures, capacities, parameters, connections, edge_types
+
+
+@pytest.mark.parametrize('method', ['euler', 'rk4', 'implicit', 'crank_nicolson'])
+def test_melting_plateau(method):
+    network = Network(*make_heated_pcm(), 0.5, method=method)
+    network.set_phase_change(1, 300., 1000., 1.)
+    assert network.melt_fractions[1] == 0.
+    assert np.isnan(network.melt_fractions[0])
+
+    # 95 J to reach the mushy zone, then half of its 1010 J
+    network.step(120)
+    assert network.temperatures[1] == pytest.approx(300.)
+    assert network.melt_fractions[1] == pytest.approx(0.5)
+
+    # 2000 J: 1000 J latent and 1000 J sensible
+    network.step(280)
+    assert network.temperatures[1] == pytest.approx(390.)
+    assert network.melt_fractions[1] == 1.
+
+
+def test_invalid_phase_change():
+    network = Network(*make_heated_pcm(), 0.5)
+    with pytest.raises(ChillValidationError):
+        network.set_phase_change(1, 300., -1000., 1.)
+    with pytest.raises(ChillValidationError):
+        network.set_phase_change(1, 300., 1000., 0.)
+    # the heater node is fixed
+    with pytest.raises(ChillValidationError):
+        network.set_phase_change(0, 300., 1000., 1.)
+    with pytest.raises(ChillValidationError):
+        network.set_phase_change(2, 300., 1000., 1.)
+
+
+def test_freezing_conserves_energy():
+    temperatures = np.array([250., 310.], dtype=np.float64)
+    capacities = np.array([np.inf, 10.], dtype=np.float64)
+    parameters = np.array([1.], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([0], dtype=np.int32)
+
+    result, results = process(temperatures, capacities, parameters, connections, edge_types,
+                              0.1, 20000, energy_balance=True,
+                              phase_changes={1: (300., 1000., 1.)})
+    energy = results['energy']
+
+    assert result[1] == pytest.approx(250., abs=1e-6)
+    assert results['melt_fractions'][1] == 0.
+    assert energy['stored'] == pytest.approx(-1600., abs=1e-3)
+    assert abs(energy['error']) < 1e-2 * 1600.
+
+
+def test_chill_phase_change_node():
+    c = Chill(dt=0.5)
+    pcm = c.define_phase_change_node(290., 10., melt_temperature=300., latent_heat=1000., width=1.)
+    c.define_heater(pcm, 10.)
+    c.setup()
+    c.run(120)
+
+    assert c.melt_fractions()[0] == pytest.approx(0.5)
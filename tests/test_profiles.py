@@ -0,0 +1,52 @@
+import numpy as np
+import pytest
+from chill import Chill, Profile, ChillValidationError
+from chill.chill import process
+
+
+def test_profile_interpolation():
+    linear = Profile([0., 10.], [0., 100.])
+    assert linear(5.) == 50.
+    assert linear(-1.) == 0.
+    assert linear(20.) == 100.
+
+    step = Profile([0., 10.], [0., 100.], interpolation='step')
+    assert step(9.9) == 0.
+    assert step(10.) == 100.
+
+    # 20 W for 10 s out of every 60 s
+    duty = Profile([0., 10.], [20., 0.], interpolation='step', period=60.)
+    assert duty(5.) == 20.
+    assert duty(30.) == 0.
+    assert duty(65.) == 20.
+
+    with pytest.raises(ChillValidationError):
+        Profile([0., 10.], [0., 1.], period=5.)
+    with pytest.raises(ChillValidationError):
+        Profile([0., 10.], [0., 1.], interpolation='cubic')
+
+
+def test_duty_cycled_heat_input():
+    temperatures = np.array([300., 300.], dtype=np.float64)
+    capacities = np.array([np.inf, 100.], dtype=np.float64)
+    parameters = np.array([0.], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([2], dtype=np.int32)
+    duty = Profile([0., 10.], [20., 0.], interpolation='step', period=60.)
+
+    temperatures = process(temperatures, capacities, parameters, connections, edge_types,
+                           0.5, 1200, heat_inputs={0: duty})
+
+    # 10 cycles of 200 J into 100 J/K
+    assert temperatures[1] == pytest.approx(320.)
+
+
+def test_chill_heater_profile():
+    c = Chill(dt=1.0)
+    plate = c.define_node(300., 100.)
+    c.define_heater(plate, (np.array([0., 100.]), np.array([0., 2.])))
+    c.setup()
+    c.run(100)
+
+    # power ramps from 0 to 2 W, 100 J in total (left Riemann sum: 99 J)
+    assert c.temperatures[0] == pytest.approx(300.99)
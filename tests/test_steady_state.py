@@ -0,0 +1,65 @@
+import numpy as np
+import pytest
+from chill import Chill, Convection, Fluid, ChillValidationError
+from chill.constants import *
+
+
+def test_radiator_equilibrium():
+    c = Chill()
+    plate = c.define_node(300*K, 100., name='plate')
+    radiator = c.define_node(300*K, 10., name='radiator')
+    space = c.define_node(3*K, np.inf, name='space')
+
+    radiation_constant = 0.9 * sigma * 0.5
+    c.define_heater(plate, 50*W)
+    c.define_thermal_conduction(plate, radiator, 0.5)
+    c.define_thermal_radiation(radiator, space, radiation_constant)
+
+    c.setup()
+    report = c.solve_steady_state()
+
+    radiator_theory = (50 / radiation_constant + 3**4) ** 0.25
+    assert abs(c.temperatures[1] - radiator_theory) < 1.e-6
+    assert abs(c.temperatures[0] - (radiator_theory + 50 * 0.5)) < 1.e-6
+    assert c.temperatures[2] == 3.
+    assert report['residual_norm'] <= 1.e-8
+
+
+def test_convection_equilibrium():
+    c = Chill(dt=1.0)
+    plate = c.define_node(300., 100.)
+    air = c.define_boundary(300.)
+    c.define_heater(plate, 50.)
+    c.define_convection(plate, air, Convection.power_law(2., exponent=1 / 3, area=0.5))
+    c.setup()
+    c.solve_steady_state()
+    # q = h * A * dT^(4/3)
+    assert c.temperatures[0] == pytest.approx(300. + 50.**0.75)
+
+
+def test_correlation_follows_the_equilibrium():
+    c = Chill()
+    plate = c.define_node(300., 100.)
+    air = c.define_boundary(300.)
+    c.define_heater(plate, 20.)
+    correlation = Convection.vertical_plate(0.2, 0.04, Fluid.air())
+    c.define_convection(plate, air, correlation)
+    c.setup()
+    report = c.solve_steady_state()
+
+    surface = c.temperatures[0]
+    assert correlation.parameter(surface, 300.) * (surface - 300.) == pytest.approx(20.)
+    assert report['residual_norm'] <= 1.e-8
+
+
+def test_steady_state_rejects_controllers():
+    c = Chill()
+    plate = c.define_node(300., 100.)
+    space = c.define_boundary(200.)
+    c.define_thermal_conduction(plate, space, 1.)
+    c.define_thermostat(plate, plate, 10., 290., 295.)
+    c.setup()
+
+    with pytest.raises(ChillValidationError):
+        c.solve_steady_state()
+    assert c.temperatures[0] == 300.
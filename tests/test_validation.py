@@ -0,0 +1,144 @@
+import numpy as np
+import pytest
+from chill.chill import (
+    Network,
+    process,
+    ChillError,
+    ChillValidationError,
+    ChillEdgeTypeError,
+    ChillInstabilityError,
+)
+
+
+def make_pair(edge_type=0):
+    # two nodes joined by a single edge
+    temperatures = np.array([300., 300.], dtype=np.float64)
+    capacities = np.array([100., 100.], dtype=np.float64)
+    parameters = np.array([1.], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([edge_type], dtype=np.int32)
+    return temperatures, capacities, parameters, connections, edge_types
+
+
+def test_all_problems_are_reported_at_once():
+    temperatures = np.array([300., 300.], dtype=np.float64)
+    capacities = np.array([100., 0.], dtype=np.float64)
+    parameters = np.array([-1., np.nan, 1.], dtype=np.float64)
+    connections = np.array([[0, 1], [0, 1], [1, 5]], dtype=np.uint64)
+    edge_types = np.array([0, 1, 0], dtype=np.int32)
+
+    with pytest.raises(ChillValidationError) as error:
+        process(*arrays, 0.1, 10)
+
+    message = str(error.value)
+    assert '4 problems' in message
+    assert 'node 1: capacity' in message
+    assert 'edge 0: Transfer resistance' in message
+    assert 'edge 1: parameter is NaN' in message
+    assert 'edge 2, node 5: node index out of range' in message
+
+    assert error.value.node_index == 1
+    assert error.value.value == 0.
+    assert [p[:2] for p in error.value.problems] == [(None, 1), (0, None), (1, None), (2, 5)]
+
+
+def test_undefined_edge_type():
+    arrays = make_pair(edge_type=7)
+
+    with pytest.raises(ChillEdgeTypeError) as error:
+        process(*arrays, 0.1, 10)
+
+    assert isinstance(error.value, ChillValidationError)
+    assert error.value.edge_index == 0
+    assert error.value.value == 7
+
+
+def test_setters_raise_validation_error():
+    arrays = make_pair()
+    network = Network(*arrays, 0.1)
+
+    with pytest.raises(ChillValidationError) as error:
+        network.set_parameter(0, -1.)
+    assert isinstance(error.value, ValueError)
+    assert error.value.edge_index == 0
+    assert error.value.value == -1.
+    assert error.value.problems == [(0, None, -1., str(error.value))]
+
+    with pytest.raises(ChillValidationError) as error:
+        network.set_capacity(1, 0.)
+    assert error.value.node_index == 1
+    with pytest.raises(ChillValidationError) as error:
+        network.set_parameter(5, 1.)
+    assert error.value.edge_index == 5
+    with pytest.raises(ChillValidationError):
+        network.set_capacity(2, 100.)
+    with pytest.raises(ChillValidationError):
+        network.parameters = np.array([np.nan])
+    with pytest.raises(ChillValidationError):
+        Network(*arrays, 0.1, max_delta=0.)
+
+
+@pytest.mark.parametrize('temperature', [np.nan, np.inf])
+def test_invalid_temperatures(temperature):
+    arrays = make_pair()
+    network = Network(*arrays, 0.1)
+
+    with pytest.raises(ChillValidationError) as error:
+        network.temperatures = np.array([300., temperature])
+    assert error.value.node_index == 1
+    with pytest.raises(ChillValidationError):
+        network.temperatures = np.array([300.])
+    assert list(network.temperatures) == [300., 300.]
+
+
+@pytest.mark.parametrize('dt', [np.nan, np.inf, 0., -0.1])
+def test_invalid_time_step(dt):
+    arrays = make_pair()
+
+    with pytest.raises(ChillValidationError):
+        Network(*arrays, dt)
+    with pytest.raises(ChillValidationError):
+        process(*arrays, dt, 10, dt_limit='substep')
+    network = Network(*arrays, 0.1)
+    with pytest.raises(ChillValidationError):
+        network.dt = dt
+    assert network.dt == 0.1
+
+
+@pytest.mark.parametrize('damping', [np.nan, -0.1, 0.6])
+def test_invalid_damping(damping):
+    arrays = make_pair()
+
+    with pytest.raises(ValueError):
+        process(*arrays, 0.1, 10, method='crank_nicolson', damping=damping)
+    with pytest.raises(ChillValidationError):
+        Network(*arrays, 0.1, method='crank_nicolson', damping=damping)
+
+
+def test_invalid_options():
+    arrays = make_pair()
+    with pytest.raises(ChillValidationError):
+        Network(*arrays, 0.1, method='leapfrog')
+    with pytest.raises(ChillValidationError):
+        Network(*arrays, 0.1, dt_limit='always')
+    with pytest.raises(ChillValidationError):
+        Network(*arrays, 0.1, on_failure='ignore')
+    with pytest.raises(ChillValidationError):
+        Network(*arrays, 0.1).record(10, record_every=0)
+
+
+def test_blow_up_reports_node_and_step():
+    temperatures = np.array([1e6, 0.], dtype=np.float64)
+    capacities = np.array([1e-3, 1e-3], dtype=np.float64)
+    parameters = np.array([1e-6], dtype=np.float64)
+    connections = np.array([[0, 1]], dtype=np.uint64)
+    edge_types = np.array([0], dtype=np.int32)
+
+    with pytest.raises(ChillInstabilityError) as error:
+        process(*arrays, 1.0, 10)
+
+    assert isinstance(error.value, ChillError)
+    assert isinstance(error.value, RuntimeError)
+    assert error.value.node_index in (0, 1)
+    assert error.value.step == 0
+    assert error.value.time == 0.